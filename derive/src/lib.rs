@@ -1,118 +1,243 @@
 extern crate proc_macro;
 
-use proc_macro2::{Ident, TokenStream, TokenTree};
+use proc_macro2::{Ident, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::{
-    spanned::Spanned, parse_macro_input, Attribute, AttrStyle, DeriveInput, Data,
-    DataStruct, Fields, Type,
+    ext::IdentExt, spanned::Spanned, parse_macro_input, Attribute, AttrStyle, DeriveInput, Data,
+    DataStruct, DataUnion, Error, Field, Fields, Generics, Lit, Meta, NestedMeta, Result, Type,
+    WherePredicate,
+    parse::{Parse, Parser},
+    punctuated::Punctuated, token::Comma,
 };
 
 // most of this code comes directly from bytemuck_derive, with slight modifications
 
-fn get_ident_from_stream(tokens: TokenStream) -> Option<Ident> {
-    match tokens.into_iter().next() {
-        Some(TokenTree::Group(group)) => get_ident_from_stream(group.stream()),
-        Some(TokenTree::Ident(ident)) => Some(ident),
-        _ => None,
+/// The layout options given by the `#[repr(...)]` attributes of a type.
+#[derive(Default)]
+struct Repr {
+    c: bool,
+    transparent: bool,
+    /// The maximum field alignment given by `packed` or `packed(N)`.
+    packed: Option<usize>,
+    /// The minimum alignment given by `align(N)`.
+    align: Option<usize>,
+    /// An integer representation, such as `u8`, which is only meaningful for enums.
+    int: Option<Ident>,
+    /// The first `#[repr(...)]` attribute, for error reporting.
+    attr: Option<Attribute>,
+}
+impl Repr {
+    fn has_modifiers(&self) -> bool {
+        self.packed.is_some() || self.align.is_some()
     }
 }
 
-fn get_attr(attributes: &[Attribute], attr_name: &str) -> Option<Ident> {
+fn get_repr_int(lit: &Lit) -> Result<usize> {
+    match lit {
+        Lit::Int(int) => match int.base10_parse::<usize>() {
+            Ok(value) if value.is_power_of_two() => Ok(value),
+            _ => Err(Error::new_spanned(lit, "expected a power of two")),
+        },
+        _ => Err(Error::new_spanned(lit, "expected a power of two")),
+    }
+}
+
+/// Parse every `#[repr(...)]` attribute of a type, as a type can have several.
+fn parse_repr(attributes: &[Attribute]) -> Result<Repr> {
+    let mut repr = Repr::default();
+
     for attr in attributes {
-        if let (
-            AttrStyle::Outer,
-            Some(outer_ident),
-            Some(inner_ident)
-        ) = (
-            &attr.style,
-            attr.path.get_ident(),
-            get_ident_from_stream(attr.tokens.clone())
-        ) {
-            if outer_ident.to_string() == attr_name { return Some(inner_ident); }
+        if !matches!(attr.style, AttrStyle::Outer) || !attr.path.is_ident("repr") { continue; }
+        if repr.attr.is_none() { repr.attr = Some(attr.clone()); }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[repr(...)]")),
+        };
+
+        for nested in &list.nested {
+            let meta = match nested {
+                NestedMeta::Meta(meta) => meta,
+                NestedMeta::Lit(lit) => return Err(Error::new_spanned(lit, "unexpected literal in #[repr(...)]")),
+            };
+
+            match meta {
+                Meta::Path(path) if path.is_ident("C") => repr.c = true,
+                Meta::Path(path) if path.is_ident("transparent") => repr.transparent = true,
+                Meta::Path(path) if path.is_ident("packed") => repr.packed = Some(1),
+                Meta::List(inner) if inner.path.is_ident("packed") || inner.path.is_ident("align") => {
+                    let value = match inner.nested.first() {
+                        Some(NestedMeta::Lit(lit)) if inner.nested.len() == 1 => get_repr_int(lit)?,
+                        _ => return Err(Error::new_spanned(inner, "expected a single integer, e.g. packed(2) or align(16)")),
+                    };
+
+                    if inner.path.is_ident("packed") { repr.packed = Some(value); }
+                    else { repr.align = Some(value.max(repr.align.unwrap_or(1))); }
+                },
+                Meta::Path(path) if path.get_ident().is_some() => repr.int = path.get_ident().cloned(),
+                _ => return Err(Error::new_spanned(meta, "unrecognized repr option")),
+            }
         }
     }
 
-    None
+    Ok(repr)
 }
 
-fn verify_attributes(attributes: &[Attribute]) -> Result<(), &'static str> {
-    let repr_attr = get_attr(attributes, "repr");
-    let error_str = "Castable requires #[repr(C)], #[repr(transparent), #[repr(packed)] or #[repr(align)]";
+/// Check that the type's repr gives it a layout that `Castable` can rely on.
+///
+/// Accepted layouts are `transparent`, `C`, `C, packed`, `C, packed(N)`, `C, align(N)`, and `packed`
+/// or `packed(N)` on their own. Without `C`, rust may reorder fields, so `align(N)` alone is rejected.
+/// A packed struct's field order is verified by the padding check, which compares each field's
+/// offset with the end of the field declared before it.
+fn verify_attributes(input: &DeriveInput) -> Result<Repr> {
+    let error_str = "Castable requires #[repr(C)], #[repr(transparent)], #[repr(packed)] or #[repr(C, align(N))]";
+    let repr = parse_repr(&input.attrs)?;
+    let attr = match &repr.attr {
+        Some(attr) => attr,
+        None => return Err(Error::new_spanned(&input.ident, format!("{}, but `{}` has no #[repr] attribute", error_str, input.ident))),
+    };
 
-    match repr_attr {
-        Some(ident) => {
-            let repr_string = ident.to_string();
+    if let Some(int) = &repr.int {
+        return Err(Error::new_spanned(int, format!("{}, found #[repr({})]", error_str, int)));
+    }
 
-            match repr_string.as_str() {
-                "C" => Ok(()),
-                "transparent" => Ok(()),
-                "packed" => Ok(()),
-                "align" => Ok(()),
-                _ => Err(error_str),
-            }
-        },
-        None => Err(error_str)
+    if repr.transparent && (repr.c || repr.has_modifiers()) {
+        return Err(Error::new_spanned(attr, "#[repr(transparent)] cannot be combined with other repr options"));
+    }
+
+    if repr.packed.is_some() && repr.align.is_some() {
+        return Err(Error::new_spanned(attr, "#[repr(packed)] cannot be combined with #[repr(align)]"));
+    }
+
+    if !repr.c && !repr.transparent && repr.packed.is_none() {
+        let message = match repr.align {
+            Some(_) => "#[repr(align(N))] does not guarantee field order, use #[repr(C, align(N))] instead".to_string(),
+            None => error_str.to_string(),
+        };
+
+        return Err(Error::new_spanned(attr, message));
     }
+
+    Ok(repr)
 }
 
-fn get_struct_fields(input: &DeriveInput) -> Result<&Fields, &'static str> {
+fn get_struct_fields(input: &DeriveInput) -> Result<&Fields> {
     if let Data::Struct(DataStruct { fields, .. }) = &input.data {
         Ok(fields)
     } else {
-        Err("deriving this trait is only supported for structs")
+        Err(Error::new_spanned(&input.ident, "deriving this trait is only supported for structs"))
+    }
+}
+
+fn get_castable_fields(input: &DeriveInput) -> Result<Vec<&Field>> {
+    match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => Ok(fields.iter().collect()),
+        Data::Union(DataUnion { fields, .. }) => Ok(fields.named.iter().collect()),
+        _ => Err(Error::new_spanned(&input.ident, "Castable can only be derived for structs and unions")),
+    }
+}
+
+/// Get the tokens used to access a field and the name used to describe it.
+fn get_field_member(index: usize, field: &Field) -> (TokenStream, String) {
+    match &field.ident {
+        Some(ident) => (quote!(#ident), ident.unraw().to_string()),
+        None => {
+            let index = syn::Index::from(index);
+            (quote!(#index), index.index.to_string())
+        },
     }
 }
 
 fn get_field_types<'a>(
-    fields: &'a Fields,
+    fields: &'a [&'a Field],
 ) -> impl Iterator<Item = &'a Type> + 'a {
     fields.iter().map(|field| &field.ty)
 }
 
 fn generate_assert_no_padding(
     input: &DeriveInput,
-) -> Result<TokenStream, &'static str> {
+) -> Result<TokenStream> {
     let struct_type = &input.ident;
-    let span = input.ident.span();
-    let fields = get_struct_fields(input)?;
+    let fields = get_castable_fields(input)?;
+    let mut asserts = Vec::<TokenStream>::new();
+    let mut end = quote!(0usize);
+    let mut last_name = None;
 
-    let mut field_types = get_field_types(&fields);
-    let size_sum = if let Some(first) = field_types.next() {
-        let size_first = quote_spanned!(span => ::std::mem::size_of::<#first>());
-        let size_rest =
-            quote_spanned!(span => #( + ::std::mem::size_of::<#field_types>() )*);
+    // compare each field's offset against the end of the previous one, so the error names where the padding is
+    for (index, field) in fields.iter().enumerate() {
+        let ty = &field.ty;
+        let (member, field_name) = get_field_member(index, field);
+        let message = format!("`{}` has padding before field `{}`, which Castable types must not contain", struct_type, field_name);
+        let span = field.ident.as_ref().map_or(ty.span(), |ident| ident.span());
 
-        quote_spanned!(span => #size_first#size_rest)
-    } else {
-        quote_spanned!(span => 0)
+        asserts.push(quote_spanned! {span =>
+            const _: () = ::std::assert!(::std::mem::offset_of!(#struct_type, #member) == #end, #message);
+        });
+
+        end = quote!(::std::mem::offset_of!(#struct_type, #member) + ::std::mem::size_of::<#ty>());
+        last_name = Some(field_name);
+    }
+
+    let message = match last_name {
+        Some(name) => format!("`{}` has padding after its last field `{}`, which Castable types must not contain", struct_type, name),
+        None => format!("`{}` has padding, which Castable types must not contain", struct_type),
     };
 
-    Ok(quote_spanned! {span => const _: fn() = || {
-        struct TypeWithoutPadding([u8; #size_sum]);
-        let _ = ::std::mem::transmute::<#struct_type, TypeWithoutPadding>;
-    };})
+    asserts.push(quote_spanned! {struct_type.span() =>
+        const _: () = ::std::assert!(::std::mem::size_of::<#struct_type>() == #end, #message);
+    });
+
+    Ok(quote!(#(#asserts)*))
+}
+
+fn generate_assert_union_sizes(
+    input: &DeriveInput,
+) -> Result<TokenStream> {
+    let union_type = &input.ident;
+    let fields = get_castable_fields(input)?;
+
+    // if a variant is smaller than the union, the bytes past its end would be padding
+    let asserts = fields.iter().enumerate().map(|(index, field)| {
+        let ty = &field.ty;
+        let (_, field_name) = get_field_member(index, field);
+        let message = format!("variant `{}` is smaller than the union `{}`, so the union would contain padding", field_name, union_type);
+
+        quote_spanned! {ty.span() =>
+            const _: () = ::std::assert!(::std::mem::size_of::<#ty>() == ::std::mem::size_of::<#union_type>(), #message);
+        }
+    });
+
+    Ok(quote!(#(#asserts)*))
 }
 
 fn generate_assert_castable(
   input: &DeriveInput
-) -> Result<TokenStream, &'static str> {
+) -> Result<TokenStream> {
     let trait_ = quote!(::pkbuffer::Castable);
-    // let (impl_generics, _ty_generics, where_clause) = input.generics.split_for_impl();
-    let fields = get_struct_fields(input)?;
-    let span = input.span();
-    let field_types = get_field_types(&fields);
-    
-    Ok(quote_spanned! {span => #(const _: fn() = || {
-        fn check() {
-            fn assert_impl<T: #trait_>() {}
-            assert_impl::<#field_types>();
-        }
-    };)*})
+    let fields = get_castable_fields(input)?;
+
+    // span each check to its field type, so an error points at the field that isn't castable
+    let asserts = get_field_types(&fields).map(|ty| quote_spanned! {ty.span() =>
+        const _: fn() = || {
+            #[allow(dead_code)]
+            fn check() {
+                fn assert_impl<T: #trait_>() {}
+                assert_impl::<#ty>();
+            }
+        };
+    });
+
+    Ok(quote!(#(#asserts)*))
 }
 
-fn verify_struct_members(input: &DeriveInput) -> Result<TokenStream, &'static str> {
+fn verify_struct_members(input: &DeriveInput, repr: &Repr) -> Result<TokenStream> {
     if !input.generics.params.is_empty() {
-        return Err("Castable cannot be derived for structures with generic parameters");
+        // a transparent wrapper has the layout of its one non-zero-sized field, so it can't have padding
+        // of its own. the fields are required to be castable by the where clause of the impl instead.
+        return match repr.transparent {
+            true => Ok(TokenStream::new()),
+            false => Err(Error::new_spanned(&input.generics, "Castable can only be derived for generic structures if they are #[repr(transparent)]")),
+        };
     }
 
     let assert_no_padding = generate_assert_no_padding(input)?;
@@ -124,40 +249,544 @@ fn verify_struct_members(input: &DeriveInput) -> Result<TokenStream, &'static st
     ))
 }
 
-fn derive_castable_verify(input: DeriveInput) -> Result<TokenStream, &'static str> {
+fn verify_union_members(input: &DeriveInput, repr: &Repr) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "Castable cannot be derived for unions with generic parameters"));
+    }
+
+    if !repr.c {
+        return match &repr.attr {
+            Some(attr) => Err(Error::new_spanned(attr, "Castable requires unions to be #[repr(C)]")),
+            None => Err(Error::new_spanned(&input.ident, "Castable requires unions to be #[repr(C)]")),
+        };
+    }
+
+    let assert_union_sizes = generate_assert_union_sizes(input)?;
+    let assert_fields_are_castable = generate_assert_castable(input)?;
+
+    Ok(quote!(
+        #assert_union_sizes
+        #assert_fields_are_castable
+    ))
+}
+
+/// Get the generics of the input with every field type bound by `Castable`.
+fn generate_castable_generics(input: &DeriveInput) -> Result<Generics> {
+    let mut generics = input.generics.clone();
+
+    if generics.params.is_empty() { return Ok(generics); }
+
+    let fields = get_castable_fields(input)?;
+    let where_clause = generics.make_where_clause();
+
+    for ty in get_field_types(&fields) {
+        where_clause.predicates.push(WherePredicate::parse.parse2(quote!(#ty: ::pkbuffer::Castable)).unwrap());
+    }
+
+    Ok(generics)
+}
+
+fn generate_castable_layout(input: &DeriveInput, generics: &Generics) -> Result<TokenStream> {
     let name = &input.ident;
-    let castable_trait = quote!(::pkbuffer::Castable);
+    let layout_trait = quote!(::pkbuffer::CastableLayout);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let is_union = matches!(input.data, Data::Union(_));
+    let fields = get_castable_fields(input)?;
+    let entries = fields.iter().enumerate().map(|(index, field)| {
+        let ty = &field.ty;
+        let padding = field.attrs.iter().any(|attr| attr.path.is_ident("padding"));
+        let (member, field_name) = get_field_member(index, field);
+        // every variant of a #[repr(C)] union starts at the beginning of the union
+        let offset = if is_union { quote!(0) }
+                     else { quote!(::std::mem::offset_of!(Self, #member)) };
 
-    verify_attributes(&input.attrs)?;
-    let struct_asserts = verify_struct_members(&input)?;
+        quote! {
+            ::pkbuffer::FieldLayout {
+                name: #field_name,
+                offset: #offset,
+                size: ::std::mem::size_of::<#ty>(),
+                type_name: ::std::any::type_name::<#ty>(),
+                padding: #padding,
+            }
+        }
+    });
 
     Ok(quote! {
-        #struct_asserts
-
-        unsafe impl #castable_trait for #name {}
+        impl #impl_generics #layout_trait for #name #ty_generics #where_clause {
+            fn field_layout() -> ::std::vec::Vec<::pkbuffer::FieldLayout> {
+                ::std::vec![#(#entries),*]
+            }
+        }
     })
 }
 
-fn derive_castable_panic(input: DeriveInput) -> TokenStream {
-    derive_castable_verify(input).unwrap_or_else(|err| {
-        quote! { compile_error!(#err); }
+fn derive_castable_verify(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let castable_trait = quote!(::pkbuffer::Castable);
+
+    let repr = verify_attributes(&input)?;
+    let asserts = match &input.data {
+        Data::Union(_) => verify_union_members(&input, &repr)?,
+        _ => verify_struct_members(&input, &repr)?,
+    };
+    let generics = generate_castable_generics(&input)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let layout = generate_castable_layout(&input, &generics)?;
+
+    Ok(quote! {
+        #asserts
+
+        unsafe impl #impl_generics #castable_trait for #name #ty_generics #where_clause {}
+
+        #layout
     })
 }
-    
+
 /// Derive the `Castable` trait for a given object.
 ///
 /// This macro ensures that most of the safety requirements for the `Castable` trait are met:
 ///
 /// * The type does not contain any padding bytes.
 /// * The type's members are also `Castable`.
-/// * The type is `#[repr(C)]`, `#[repr(transparent)]`, `#[repr(packed)]` or `#[repr(align)]`.
-/// * The type must not use generics.
+/// * The type has a layout with a defined field order (see below).
+/// * The type must not use generics, unless it is `#[repr(transparent)]`.
 ///
 /// If one of these traits aren't met, the derive macro will fail.
-#[proc_macro_derive(Castable)]
+///
+/// The accepted layouts are:
+///
+/// * `#[repr(C)]`
+/// * `#[repr(C, packed)]` and `#[repr(C, packed(N))]`
+/// * `#[repr(C, align(N))]`
+/// * `#[repr(packed)]` and `#[repr(packed(N))]`
+/// * `#[repr(transparent)]`
+///
+/// `#[repr(align(N))]` without `C` is rejected, because it leaves the field order up to the compiler.
+/// Separate repr attributes such as `#[repr(C)] #[repr(align(8))]` are combined. Whatever the layout,
+/// each field must start where the previous one ended and the type must end where its last field
+/// ends, so `packed(N)` and `align(N)` are only accepted if they don't introduce padding.
+///
+/// Unions are also supported if they are `#[repr(C)]`. Every variant must be `Castable` and
+/// have the same size as the union, so that no variant leaves padding bytes behind.
+///
+/// Generic `#[repr(transparent)]` wrappers implement `Castable` only when each of their field
+/// types does, e.g. `struct Le<T>(T)` is `Castable` when `T: Castable`.
+///
+/// The macro also implements `CastableLayout`, which describes the name, offset, size and
+/// type of each field of the structure. Fields marked with `#[padding]` are reported as padding.
+#[proc_macro_derive(Castable, attributes(padding))]
 pub fn derive_castable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let expanded = derive_castable_panic(parse_macro_input!(input as DeriveInput));
-    
+    let expanded = derive_castable_verify(parse_macro_input!(input as DeriveInput)).unwrap_or_else(|err| {
+        err.to_compile_error()
+    });
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+/// Get the alignment of a field of the given type, capped by `packed(N)` if present.
+fn generate_field_align(ty: &Type, packed: Option<usize>) -> TokenStream {
+    match packed {
+        Some(packed) => quote!(if ::std::mem::align_of::<#ty>() > #packed { #packed } else { ::std::mem::align_of::<#ty>() }),
+        None => quote!(::std::mem::align_of::<#ty>()),
+    }
+}
+
+/// Calculate the offset of the end of the given field types when laid out in order with `#[repr(C)]` rules.
+fn generate_repr_c_end(types: &[&Type], packed: Option<usize>) -> TokenStream {
+    let aligns = types.iter().map(|ty| generate_field_align(ty, packed));
+
+    quote! {{
+        let mut offset = 0usize;
+        #(
+            offset = offset.next_multiple_of(#aligns) + ::std::mem::size_of::<#types>();
+        )*
+        offset
+    }}
+}
+
+fn generate_padding_field(vis: &syn::Visibility, ident: Ident, size_const: &Ident) -> Field {
+    let padding_attr = Attribute::parse_outer.parse2(quote!(#[padding])).unwrap();
+
+    Field {
+        attrs: padding_attr,
+        vis: vis.clone(),
+        ident: Some(ident),
+        colon_token: Some(Default::default()),
+        ty: Type::Verbatim(quote!([u8; #size_const])),
+    }
+}
+
+/// Insert padding fields into the given struct, returning the constants holding their sizes.
+///
+/// The sizes live in separate constants because syn can't parse the block expressions needed to
+/// calculate them inline without its "full" feature, and the `Castable` derive reparses the struct.
+fn auto_pad_fields(input: &mut DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "auto_pad cannot be used on structures with generic parameters"));
+    }
+
+    let repr = parse_repr(&input.attrs)?;
+
+    match (&repr.attr, repr.c) {
+        (_, true) => (),
+        (Some(attr), false) => return Err(Error::new_spanned(attr, "auto_pad requires #[repr(C)]")),
+        (None, false) => return Err(Error::new_spanned(&input.ident, "auto_pad requires #[repr(C)]")),
+    }
+
+    let struct_name = input.ident.unraw().to_string();
+    let named = match &mut input.data {
+        Data::Struct(DataStruct { fields: Fields::Named(named), .. }) => &mut named.named,
+        Data::Struct(_) => return Err(Error::new_spanned(&input.ident, "auto_pad is only supported for structs with named fields")),
+        _ => return Err(Error::new_spanned(&input.ident, "auto_pad is only supported for structs")),
+    };
+
+    let fields = named.iter().cloned().collect::<Vec<Field>>();
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<&Type>>();
+    let mut padded = Punctuated::<Field, Comma>::new();
+    let mut size_consts = Vec::<TokenStream>::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        // raw identifiers such as r#type can't be pasted into another identifier as-is
+        let pad_ident = Ident::new(&format!("_pad_{}", ident.unraw()), ident.span());
+        let size_const = Ident::new(&format!("__PKBUFFER_PAD_{}_{}", struct_name, ident.unraw()), ident.span());
+        let end = generate_repr_c_end(&types[..=index], repr.packed);
+        let align = match types.get(index+1) {
+            Some(next) => generate_field_align(next, repr.packed),
+            // pad the end of the struct out to the alignment of its most aligned field, or align(N) if greater
+            None => {
+                let aligns = types.iter().map(|ty| generate_field_align(ty, repr.packed));
+                let min_align = repr.align.unwrap_or(1);
+
+                quote! {{
+                    let mut align = #min_align;
+                    #( if #aligns > align { align = #aligns; } )*
+                    align
+                }}
+            },
+        };
+
+        size_consts.push(quote! {
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals)]
+            const #size_const: usize = {
+                let end = #end;
+                let align = #align;
+                (align - end % align) % align
+            };
+        });
+
+        padded.push(field.clone());
+        padded.push(generate_padding_field(&field.vis, pad_ident, &size_const));
+    }
+
+    *named = padded;
+
+    Ok(quote!(#(#size_consts)*))
+}
+
+fn castable_attribute_verify(args: syn::AttributeArgs, mut input: DeriveInput) -> Result<TokenStream> {
+    let mut auto_pad = false;
+
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("auto_pad") => auto_pad = true,
+            _ => return Err(Error::new_spanned(arg, "unknown castable option, expected auto_pad")),
+        }
+    }
+
+    let size_consts = if auto_pad { auto_pad_fields(&mut input)? } else { TokenStream::new() };
+
+    Ok(quote! {
+        #size_consts
+        #input
+    })
+}
+
+/// Modify a `Castable` structure before it is derived.
+///
+/// Currently the only option is `auto_pad`, as in `#[castable(auto_pad)]`. Rather than failing the
+/// `Castable` derive when a `#[repr(C)]` struct contains implicit padding, it inserts an explicit
+/// `[u8; N]` padding array after each field, sized to wherever the compiler would have padded. The
+/// array following field `foo` is named `_pad_foo` and is zero-sized if no padding is needed there.
+/// Padding fields are marked with `#[padding]`, so this attribute must come before
+/// `#[derive(Castable)]`. When constructing the struct, padding fields can be filled with
+/// `Default::default()`. `packed(N)` and `align(N)` modifiers are taken into account, so
+/// `#[repr(C, align(16))]` pads the end of the struct out to 16 bytes.
+///
+/// ```ignore
+/// #[castable(auto_pad)]
+/// #[repr(C)]
+/// #[derive(Copy, Clone, Castable)]
+/// struct Entry {
+///     kind: u8,
+///     // _pad_kind: [u8; 3]
+///     value: u32,
+///     // _pad_value: [u8; 0]
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn castable(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(args as syn::AttributeArgs);
+    let expanded = castable_attribute_verify(args, parse_macro_input!(input as DeriveInput)).unwrap_or_else(|err| {
+        err.to_compile_error()
+    });
+
     proc_macro::TokenStream::from(expanded)
 }
 
+
+/// How a field of a `BufferParse` structure is laid out in the buffer.
+enum ParseKind {
+    /// The field is parsed by its own `BufferParse` implementation.
+    Single,
+    /// The field is a `Vec` whose element count is given by an expression.
+    Count(TokenStream),
+    /// The field is a `Vec` whose element count precedes it as the given type.
+    LenPrefix(TokenStream),
+}
+
+struct ParseField<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    kind: ParseKind,
+    offset: Option<TokenStream>,
+    align: Option<usize>,
+}
+
+fn get_expr_attr(attr: &Attribute) -> Result<TokenStream> {
+    let error_str = "expected an expression string, e.g. #[count = \"header.count\"]";
+
+    match attr.parse_meta() {
+        Ok(Meta::NameValue(name_value)) => match &name_value.lit {
+            Lit::Str(lit) => lit.value().parse::<TokenStream>()
+                .map_err(|_| Error::new_spanned(lit, "the expression in this attribute is not valid Rust")),
+            lit => Err(Error::new_spanned(lit, error_str)),
+        },
+        _ => Err(Error::new_spanned(attr, error_str)),
+    }
+}
+
+fn get_len_prefix_attr(attr: &Attribute) -> Result<TokenStream> {
+    let error_str = "expected an integer type, e.g. #[len_prefix(u16)]";
+
+    match attr.parse_meta() {
+        Ok(Meta::List(list)) if list.nested.len() == 1 => match list.nested.first() {
+            Some(NestedMeta::Meta(Meta::Path(path))) => Ok(quote!(#path)),
+            Some(nested) => Err(Error::new_spanned(nested, error_str)),
+            None => Err(Error::new_spanned(attr, error_str)),
+        },
+        _ => Err(Error::new_spanned(attr, error_str)),
+    }
+}
+
+fn get_align_attr(attr: &Attribute) -> Result<usize> {
+    let error_str = "expected a non-zero integer alignment, e.g. #[align(8)]";
+
+    match attr.parse_meta() {
+        Ok(Meta::List(list)) if list.nested.len() == 1 => match list.nested.first() {
+            Some(NestedMeta::Lit(Lit::Int(lit))) => match lit.base10_parse::<usize>() {
+                Ok(align) if align != 0 => Ok(align),
+                _ => Err(Error::new_spanned(lit, error_str)),
+            },
+            Some(nested) => Err(Error::new_spanned(nested, error_str)),
+            None => Err(Error::new_spanned(attr, error_str)),
+        },
+        _ => Err(Error::new_spanned(attr, error_str)),
+    }
+}
+
+fn get_parse_field(field: &Field) -> Result<ParseField<'_>> {
+    let ident = match &field.ident {
+        Some(ident) => ident,
+        None => return Err(Error::new_spanned(&field.ty, "BufferParse can only be derived for structs with named fields")),
+    };
+
+    let mut result = ParseField { ident, ty: &field.ty, kind: ParseKind::Single, offset: None, align: None };
+
+    for attr in &field.attrs {
+        if !matches!(attr.style, AttrStyle::Outer) { continue; }
+
+        let name = match attr.path.get_ident() {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+
+        match name.as_str() {
+            "count" | "len_prefix" => {
+                if !matches!(result.kind, ParseKind::Single) {
+                    return Err(Error::new_spanned(attr, "a field can only have one of #[count] or #[len_prefix]"));
+                }
+
+                result.kind = if name == "count" { ParseKind::Count(get_expr_attr(attr)?) }
+                              else { ParseKind::LenPrefix(get_len_prefix_attr(attr)?) };
+            },
+            "offset" => result.offset = Some(get_expr_attr(attr)?),
+            "align" => result.align = Some(get_align_attr(attr)?),
+            _ => (),
+        }
+    }
+
+    Ok(result)
+}
+
+fn get_parse_fields(input: &DeriveInput) -> Result<Vec<ParseField<'_>>> {
+    match get_struct_fields(input)? {
+        Fields::Named(named) => named.named.iter().map(get_parse_field).collect(),
+        Fields::Unit => Ok(Vec::new()),
+        Fields::Unnamed(unnamed) => Err(Error::new_spanned(unnamed, "BufferParse can only be derived for structs with named fields")),
+    }
+}
+
+fn generate_parse_fn(fields: &[ParseField]) -> TokenStream {
+    let buffer_parse = quote!(::pkbuffer::BufferParse);
+    let idents = fields.iter().map(|field| field.ident);
+    let steps = fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = field.ty;
+        let seek = field.offset.as_ref().map(|expr| quote! {
+            __cursor = __offset.checked_add(::pkbuffer::ParseIndex::parse_index(&(#expr))?)
+                .ok_or(::pkbuffer::Error::Overflow(__offset))?;
+        });
+        let align = field.align.map(|align| quote! {
+            __cursor = ::pkbuffer::align_offset(__cursor, #align)?;
+        });
+        let read = match &field.kind {
+            ParseKind::Single => quote! {
+                let (#ident, __size) = <#ty as #buffer_parse>::parse(__buffer, __cursor)?;
+            },
+            ParseKind::Count(expr) => quote! {
+                let (#ident, __size): (#ty, usize) = ::pkbuffer::parse_vec(__buffer, __cursor, ::pkbuffer::ParseIndex::parse_index(&(#expr))?)?;
+            },
+            ParseKind::LenPrefix(prefix) => quote! {
+                let (__len, __size) = ::pkbuffer::parse_len_prefix::<#prefix, _>(__buffer, __cursor)?;
+                __cursor = __cursor.checked_add(__size).ok_or(::pkbuffer::Error::Overflow(__cursor))?;
+                let (#ident, __size): (#ty, usize) = ::pkbuffer::parse_vec(__buffer, __cursor, __len)?;
+            },
+        };
+
+        quote! {
+            #seek
+            #align
+            #read
+            __cursor = __cursor.checked_add(__size).ok_or(::pkbuffer::Error::Overflow(__cursor))?;
+            __end = ::std::cmp::max(__end, __cursor);
+        }
+    });
+
+    quote! {
+        #[allow(unused_mut, unused_assignments)]
+        fn parse<B: ::pkbuffer::Buffer + ?Sized>(__buffer: &B, __offset: usize) -> ::std::result::Result<(Self, usize), ::pkbuffer::Error> {
+            let mut __cursor = __offset;
+            let mut __end = __offset;
+            #(#steps)*
+            Ok((Self { #(#idents),* }, __end - __offset))
+        }
+    }
+}
+
+fn generate_write_into_fn(fields: &[ParseField]) -> TokenStream {
+    let buffer_parse = quote!(::pkbuffer::BufferParse);
+    let idents = fields.iter().map(|field| field.ident);
+    let steps = fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = field.ty;
+        let seek = field.offset.as_ref().map(|expr| quote! {
+            let __target = __start.checked_add(::pkbuffer::ParseIndex::parse_index(&(#expr))?)
+                .ok_or(::pkbuffer::Error::Overflow(__start))?;
+
+            if __buffer.len() > __target {
+                return Err(::pkbuffer::Error::OutOfBounds(__target, __buffer.len()));
+            }
+
+            __buffer.resize(__target, 0);
+        });
+        let align = field.align.map(|align| quote! {
+            let __target = ::pkbuffer::align_offset(__buffer.len(), #align)?;
+            __buffer.resize(__target, 0);
+        });
+        let write = match &field.kind {
+            ParseKind::Single => quote! {
+                <#ty as #buffer_parse>::write_into(#ident, __buffer)?;
+            },
+            ParseKind::Count(expr) => quote! {
+                let __count = ::pkbuffer::ParseIndex::parse_index(&(#expr))?;
+
+                if #ident.len() != __count {
+                    return Err(::pkbuffer::Error::SizeMismatch(__count, #ident.len()));
+                }
+
+                ::pkbuffer::write_vec(#ident, __buffer)?;
+            },
+            ParseKind::LenPrefix(prefix) => quote! {
+                ::pkbuffer::write_len_prefix::<#prefix>(#ident.len(), __buffer)?;
+                ::pkbuffer::write_vec(#ident, __buffer)?;
+            },
+        };
+
+        quote! {
+            #seek
+            #align
+            #write
+        }
+    });
+
+    quote! {
+        #[allow(unused_variables)]
+        fn write_into(&self, __buffer: &mut ::pkbuffer::VecBuffer) -> ::std::result::Result<(), ::pkbuffer::Error> {
+            use ::pkbuffer::Buffer;
+
+            let __start = __buffer.len();
+            let Self { #(#idents),* } = self;
+            #(#steps)*
+            Ok(())
+        }
+    }
+}
+
+fn derive_buffer_parse_verify(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "BufferParse cannot be derived for structures with generic parameters"));
+    }
+
+    let fields = get_parse_fields(&input)?;
+    let parse_fn = generate_parse_fn(&fields);
+    let write_into_fn = generate_write_into_fn(&fields);
+
+    Ok(quote! {
+        impl ::pkbuffer::BufferParse for #name {
+            #parse_fn
+            #write_into_fn
+        }
+    })
+}
+
+/// Derive the `BufferParse` trait for a given struct.
+///
+/// Each named field is parsed in order with its own `BufferParse` implementation. Every
+/// `Castable` type is `BufferParse`, as are other structs deriving this macro. The
+/// following field attributes change how a field is laid out:
+///
+/// * `#[count = "expr"]`: the field is a `Vec` of `expr` elements. The expression can refer
+///   to any previously parsed field, e.g. `#[count = "header.entries"]` or `#[count = "count"]`,
+///   and must evaluate to an integer or a reference to one (see `pkbuffer::ParseIndex`).
+/// * `#[len_prefix(u16)]`: the field is a `Vec` whose element count precedes it as the given
+///   integer type.
+/// * `#[offset = "expr"]`: the field starts `expr` bytes from the start of the struct, rather
+///   than directly after the previous field. The expression can refer to previously parsed fields.
+/// * `#[align(8)]`: the field starts at the next offset in the buffer aligned to the given
+///   boundary. When writing, the gap is filled with zeroes.
+///
+/// The generated `parse` function returns the struct and the number of bytes it spans, and the
+/// generated `write_into` function appends the struct to a `VecBuffer`. Counts and offsets which
+/// are negative or push the cursor past `usize::MAX` return an `Error::Overflow` error.
+#[proc_macro_derive(BufferParse, attributes(count, len_prefix, offset, align))]
+pub fn derive_buffer_parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let expanded = derive_buffer_parse_verify(parse_macro_input!(input as DeriveInput)).unwrap_or_else(|err| {
+        err.to_compile_error()
+    });
+
+    proc_macro::TokenStream::from(expanded)
+}
@@ -0,0 +1,9 @@
+use pkbuffer::Castable;
+
+#[repr(C, align(8))]
+#[derive(Copy, Clone, Castable)]
+struct Entry {
+    kind: u32,
+}
+
+fn main() {}
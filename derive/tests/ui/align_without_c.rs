@@ -0,0 +1,12 @@
+use pkbuffer::Castable;
+
+#[repr(align(16))]
+#[derive(Copy, Clone, Castable)]
+struct Vector {
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32,
+}
+
+fn main() {}
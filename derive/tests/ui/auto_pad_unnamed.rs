@@ -0,0 +1,8 @@
+use pkbuffer::{castable, Castable};
+
+#[castable(auto_pad)]
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+struct Entry(u8, u32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(u8)]
+#[derive(Copy, Clone, Castable)]
+enum Kind {
+    A,
+    B,
+}
+
+fn main() {}
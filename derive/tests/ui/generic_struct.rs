@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+fn main() {}
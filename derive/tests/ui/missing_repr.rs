@@ -0,0 +1,9 @@
+use pkbuffer::Castable;
+
+#[derive(Copy, Clone, Castable)]
+struct Header {
+    magic: u16,
+    version: u16,
+}
+
+fn main() {}
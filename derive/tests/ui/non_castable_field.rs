@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+struct Flags {
+    raw: u8,
+    enabled: bool,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+struct Entry {
+    kind: u8,
+    value: u32,
+}
+
+fn main() {}
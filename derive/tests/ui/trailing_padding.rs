@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+struct Entry {
+    value: u32,
+    kind: u8,
+}
+
+fn main() {}
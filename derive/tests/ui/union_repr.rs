@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(packed)]
+#[derive(Copy, Clone, Castable)]
+union Value {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pkbuffer::Castable;
+
+#[repr(C)]
+#[derive(Copy, Clone, Castable)]
+union Value {
+    dword: u32,
+    byte: u8,
+}
+
+fn main() {}
@@ -1,5 +1,45 @@
-use crate::{Castable, Error, ref_to_bytes, slice_ref_to_bytes, bytes_to_ref, bytes_to_mut_ref};
+use crate::{Algorithm, Castable, CastableLayout, Checksum, EntropyWindows, Error, SubBufferMut, TypedView, TypedViewMut, histogram_entropy, ref_to_bytes, slice_ref_to_bytes, bytes_to_ref, bytes_to_mut_ref};
 use memchr::memmem;
+#[cfg(feature = "rayon")]
+use crate::{par_search, par_search_dynamic, par_search_many};
+
+/// Attach the untyped operation *name* to an error. See [`Error::Context`](Error::Context).
+fn operation(name: &'static str, offset: Option<usize>, length: usize) -> impl Fn(Error) -> Error + Copy {
+    move |error| error.in_operation(name, None, offset, length)
+}
+
+/// Attach the operation *name* on type *T* to an error. See [`Error::Context`](Error::Context).
+fn typed_operation<T: ?Sized>(name: &'static str, offset: Option<usize>, length: usize) -> impl Fn(Error) -> Error + Copy {
+    move |error| error.in_operation(name, Some(std::any::type_name::<T>()), offset, length)
+}
+
+/// Attach the operation *name* on the given *range* of a buffer of size *len* to an error.
+fn range_operation<R: std::ops::RangeBounds<usize>>(name: &'static str, len: usize, range: &R) -> impl Fn(Error) -> Error + Copy {
+    use std::ops::Bound;
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    operation(name, Some(start), end.saturating_sub(start))
+}
+
+/// Get the offset just past *count* objects of type *T* starting at *offset*.
+///
+/// Returns an [`Error::Overflow`](Error::Overflow) error if the span doesn't fit in a `usize`.
+fn span_end<T>(offset: usize, count: usize) -> Result<usize, Error> {
+    match std::mem::size_of::<T>().checked_mul(count).and_then(|size| size.checked_add(offset)) {
+        Some(end) => Ok(end),
+        None => Err(Error::Overflow(offset)),
+    }
+}
 
 /// The trait by which all buffer objects are derived.
 pub trait Buffer {
@@ -7,12 +47,8 @@ pub trait Buffer {
     fn len(&self) -> usize;
     /// Get the `Buffer` object as a pointer.
     fn as_ptr(&self) -> *const u8;
-    /// Get the `Buffer` object as a mutable pointer.
-    fn as_mut_ptr(&mut self) -> *mut u8;
     /// Get the `Buffer` object as a slice.
     fn as_slice(&self) -> &[u8];
-    /// Get the `Buffer` object as a mutable slice.
-    fn as_mut_slice(&mut self) -> &mut [u8];
 
     /// Get a pointer to the end of the buffer.
     ///
@@ -26,10 +62,6 @@ pub trait Buffer {
     fn as_ptr_range(&self) -> std::ops::Range<*const u8> {
         std::ops::Range::<*const u8> { start: self.as_ptr(), end: self.eob() }
     }
-    /// Get a mutable pointer range of this buffer. See [slice::as_mut_ptr_range](slice::as_mut_ptr_range) for more details.
-    fn as_mut_ptr_range(&mut self) -> std::ops::Range<*mut u8> {
-        std::ops::Range::<*mut u8> { start: self.as_mut_ptr(), end: self.eob() as *mut u8 }
-    }
     /// Check whether or not this buffer is empty.
     fn is_empty(&self) -> bool {
         self.len() == 0
@@ -49,23 +81,12 @@ pub trait Buffer {
 
         unsafe { Ok(self.as_ptr().add(offset)) }
     }
-    /// Convert an *offset* to a mutable [`u8`](u8) pointer.
-    ///
-    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset is out of bounds
-    /// of the buffer.
-    fn offset_to_mut_ptr(&mut self, offset: usize) -> Result<*mut u8, Error> {
-        if offset >= self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset));
-        }
-
-        unsafe { Ok(self.as_mut_ptr().add(offset)) }
-    }
     /// Convert a *pointer* to an offset into the buffer.
     ///
     /// Returns an [`Error::InvalidPointer`](Error::InvalidPointer) error if the given pointer is not
     /// within the range of this buffer.
     fn ptr_to_offset(&self, ptr: *const u8) -> Result<usize, Error> {
-        if !self.validate_ptr(ptr) { return Err(Error::InvalidPointer(ptr)); }
+        if !self.validate_ptr(ptr) { return Err(Error::InvalidPointer(ptr as usize)); }
 
         Ok(ptr as usize - self.as_ptr() as usize)
     }
@@ -91,23 +112,10 @@ pub trait Buffer {
     fn to_vec(&self) -> Vec<u8> {
         self.as_slice().to_vec()
     }
-    /// Swap two bytes at the given offsets. This panics if the offsets are out of bounds. See [`slice::swap`](slice::swap)
-    /// for more details.
-    fn swap(&mut self, a: usize, b: usize) {
-        self.as_mut_slice().swap(a, b);
-    }
-    /// Reverse the buffer. See [`slice::reverse`](slice::reverse) for more details.
-    fn reverse(&mut self) {
-        self.as_mut_slice().reverse();
-    }
     /// Return an iterator object ([`BufferIter`](BufferIter)) into the buffer.
     fn iter(&self) -> BufferIter<'_> {
         BufferIter { buffer: self.as_slice(), index: 0 }
     }
-    /// Return a mutable iterator object ([`BufferIterMut`](BufferIterMut)) into the buffer.
-    fn iter_mut(&mut self) -> BufferIterMut<'_> {
-        BufferIterMut { buffer: self.as_mut_slice(), index: 0 }
-    }
     /// Save this buffer to disk.
     fn save<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(), Error> {
         std::fs::write(filename, self.as_slice())?;
@@ -117,10 +125,6 @@ pub trait Buffer {
     fn get<I: std::slice::SliceIndex<[u8]>>(&self, index: I) -> Option<&I::Output> {
         self.as_slice().get(index)
     }
-    /// Get the given byte or range of bytes from the buffer as mutable. See [`slice::get_mut`](slice::get_mut) for more details.
-    fn get_mut<I: std::slice::SliceIndex<[u8]>>(&mut self, index: I) -> Option<&mut I::Output> {
-        self.as_mut_slice().get_mut(index)
-    }
     /// Get a reference to a given object within the buffer. Typically the main interface by which objects are retrieved.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset or the object's size plus
@@ -139,8 +143,89 @@ pub trait Buffer {
     /// ```
     fn get_ref<T: Castable>(&self, offset: usize) -> Result<&T, Error> {
         let size = std::mem::size_of::<T>();
-        let bytes = self.get_slice_ref::<u8>(offset, size)?;
-        bytes_to_ref::<T>(bytes)
+        let op = typed_operation::<T>("get_ref", Some(offset), size);
+        let bytes = self.get_slice_ref::<u8>(offset, size).map_err(op)?;
+        bytes_to_ref::<T>(bytes).map_err(op)
+    }
+    /// Get a reference to a given object within the buffer, verifying that its padding bytes are zero.
+    ///
+    /// This works like [`Buffer::get_ref`](Buffer::get_ref), but also checks every field marked as padding by
+    /// its [`CastableLayout`](CastableLayout), such as those inserted by
+    /// [`#[castable(auto_pad)]`](pkbuffer_derive::castable). Returns an [`Error::NonZeroPadding`](Error::NonZeroPadding)
+    /// error with the offset of the first non-zero padding byte.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, Castable, VecBuffer, castable};
+    ///
+    /// #[castable(auto_pad)]
+    /// #[repr(C)]
+    /// #[derive(Copy, Clone, Castable)]
+    /// struct Entry {
+    ///     kind: u8,
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut buffer = VecBuffer::from_data([0x01, 0x00, 0x00, 0x00, 0xEF, 0xBE, 0xAD, 0xDE]);
+    /// assert_eq!(buffer.get_ref_checked::<Entry>(0).unwrap().value, 0xDEADBEEF);
+    ///
+    /// buffer[2] = 0xFF;
+    /// assert!(buffer.get_ref_checked::<Entry>(0).is_err());
+    /// ```
+    fn get_ref_checked<T: CastableLayout>(&self, offset: usize) -> Result<&T, Error> {
+        let op = typed_operation::<T>("get_ref_checked", Some(offset), std::mem::size_of::<T>());
+        let object = self.get_ref::<T>(offset).map_err(op)?;
+        let data = ref_to_bytes::<T>(object).map_err(op)?;
+
+        for field in T::field_layout().iter().filter(|field| field.padding) {
+            if let Some(index) = data[field.offset..field.offset+field.size].iter().position(|byte| *byte != 0) {
+                return Err(op(Error::NonZeroPadding(offset+field.offset+index)));
+            }
+        }
+
+        Ok(object)
+    }
+    /// Pretty-print the object of type *T* at the given *offset*, field by field.
+    ///
+    /// Each line shows a field's offset within the buffer and within the object, its name, its type and its
+    /// raw bytes. This is handy for figuring out why a [`Buffer::get_ref`](Buffer::get_ref) call fails or returns
+    /// unexpected data. The offset need not be aligned. Returns an [`Error::OutOfBounds`](Error::OutOfBounds)
+    /// error if the object runs out of boundaries.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, Castable, VecBuffer};
+    ///
+    /// #[repr(C)]
+    /// #[derive(Copy, Clone, Castable)]
+    /// struct Header {
+    ///     magic: u16,
+    ///     version: u16,
+    /// }
+    ///
+    /// let buffer = VecBuffer::from_data([0x00, 0x4D, 0x5A, 0x01, 0x00]);
+    /// let dump = buffer.dump_struct::<Header>(1).unwrap();
+    /// assert!(dump.contains("0x1 (+0x0) magic: u16 = 4d 5a"));
+    /// assert!(dump.contains("0x3 (+0x2) version: u16 = 01 00"));
+    /// ```
+    fn dump_struct<T: CastableLayout>(&self, offset: usize) -> Result<String, Error> {
+        use std::fmt::Write;
+
+        let size = std::mem::size_of::<T>();
+        let data = self.read(offset, size).map_err(typed_operation::<T>("dump_struct", Some(offset), size))?;
+        let mut result = format!("{} at {:#x} ({:#x} bytes)\n", std::any::type_name::<T>(), offset, size);
+
+        for field in T::field_layout() {
+            let bytes = data[field.offset..field.offset+field.size].iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            // writing to a String can't fail
+            let _ = writeln!(result, "  {:#x} (+{:#x}) {}: {} = {}", offset+field.offset, field.offset, field.name, field.type_name, bytes);
+        }
+
+        Ok(result)
     }
     /// Get a reference to a given object within the buffer, but in an unaligned way.
     ///
@@ -154,34 +239,515 @@ pub trait Buffer {
     /// target processor, or unsure of the alignment situation in your data, it's best to use
     /// [`Buffer::get_ref`](Buffer::get_ref) instead.
     unsafe fn get_ref_unaligned<T>(&self, offset: usize) -> Result<&T, Error> {
-        let ptr = self.offset_to_ptr(offset)?;
         let size = std::mem::size_of::<T>();
+        let op = typed_operation::<T>("get_ref_unaligned", Some(offset), size);
+        let ptr = self.offset_to_ptr(offset).map_err(op)?;
+        let end = span_end::<T>(offset, 1).map_err(op)?;
+
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
+        }
+
+        Ok(&*(ptr as *const T))
+    }
+    /// Get a reference regardless of potential alignment issues.
+    ///
+    /// # Safety
+    /// It is not recommended you use this function if you're unaware of the alignment
+    /// situation of your processor or data. See
+    /// [`Buffer::get_ref_unaligned`](Buffer::get_ref_unaligned) for more details.
+    unsafe fn force_get_ref<T: Castable>(&self, offset: usize) -> Result<&T, Error> {
+        let op = typed_operation::<T>("force_get_ref", Some(offset), std::mem::size_of::<T>());
+
+        match self.get_ref::<T>(offset) {
+            Ok(ref_data) => Ok(ref_data),
+            Err(err) => {
+                if let Error::BadAlignment(_,_) = err.root() { self.get_ref_unaligned::<T>(offset).map_err(op) }
+                else { Err(op(err)) }
+            },
+        }
+    }
+    /// Gets a slice reference of type *T* at the given *offset* with the given *size*.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset or the
+    /// offset plus its size goes out of bounds of the buffer and returns
+    /// [`Error::BadAlignment`](Error::BadAlignment) if the acquired slice is not aligned
+    /// on the alignment boundary required by type *T*. Returns an [`Error::Overflow`](Error::Overflow)
+    /// error if the offset plus its size doesn't fit in a `usize`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hex;
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(&hex::decode("f00dbeef1deadead").unwrap());
+    ///
+    /// let slice = buffer.get_slice_ref::<u16>(0, 4);
+    /// assert!(slice.is_ok());
+    /// assert_eq!(slice.unwrap(), [0x0DF0, 0xEFBE, 0xEA1D, 0xADDE]);
+    /// ```
+    fn get_slice_ref<T: Castable>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
+        let op = typed_operation::<T>("get_slice_ref", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
+        let ptr = self.offset_to_ptr(offset).map_err(op)?;
+        let end = span_end::<T>(offset, size).map_err(op)?;
+
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
+        }
+
+        let alignment = std::mem::align_of::<T>();
+
+        if (ptr as usize) % alignment != 0 {
+            return Err(op(Error::BadAlignment(alignment, (ptr as usize) % alignment)));
+        }
+
+        unsafe { Ok(std::slice::from_raw_parts(ptr as *const T, size)) }
+    }
+    /// Gets a slice ref of type *T* at the given *offset* regardless of potential alignment
+    /// issues.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset or the offset
+    /// plus its size go out of bounds of the buffer.
+    ///
+    /// # Safety
+    /// This is an unsafe function because it gets a slice reference that is not aligned to a proper boundary, which
+    /// can trigger undefined behavior on some processors. If you're unsure of the alignment situation on your
+    /// target processor, or unsure of the alignment situation in your data, it's best to use
+    /// [`Buffer::get_slice_ref`](Buffer::get_slice_ref) instead.
+    unsafe fn get_slice_ref_unaligned<T>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
+        let op = typed_operation::<T>("get_slice_ref_unaligned", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
+        let ptr = self.offset_to_ptr(offset).map_err(op)?;
+        let slice_end = span_end::<T>(offset, size).map_err(op)?;
+
+        if slice_end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(), slice_end)));
+        }
+
+        Ok(std::slice::from_raw_parts(ptr as *const T, size))
+    }
+    /// Get a slice reference regardless of potential alignment issues.
+    ///
+    /// # Safety
+    /// It is not recommended you use this function if you're unaware of the alignment
+    /// situation of your processor or data. See [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned)
+    /// for more details.
+    unsafe fn force_get_slice_ref<T: Castable>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
+        let op = typed_operation::<T>("force_get_slice_ref", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
+
+        match self.get_slice_ref::<T>(offset, size) {
+            Ok(ref_data) => Ok(ref_data),
+            Err(err) => {
+                if let Error::BadAlignment(_,_) = err.root() { self.get_slice_ref_unaligned::<T>(offset, size).map_err(op) }
+                else { Err(op(err)) }
+            },
+        }
+    }
+    /// Read an arbitrary *size* amount of bytes from the given *offset*.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the read runs out of boundaries.
+    fn read(&self, offset: usize, size: usize) -> Result<&[u8], Error> {
+        self.get_slice_ref::<u8>(offset, size).map_err(operation("read", Some(offset), size))
+    }
+    /// Read a copy of an object of type *T* at the given *offset*.
+    ///
+    /// Unlike [`Buffer::get_ref`](Buffer::get_ref), the offset need not be aligned for type *T*, because
+    /// the object is copied out of the buffer rather than referenced. Returns an
+    /// [`Error::OutOfBounds`](Error::OutOfBounds) error if the read runs out of boundaries.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hex;
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(&hex::decode("facebabedeadbeef").unwrap());
+    /// assert_eq!(buffer.read_val::<u32>(1).unwrap(), 0xDEBEBACE);
+    /// assert_eq!(buffer.read_vals::<u16>(3, 2).unwrap(), [0xDEBE, 0xBEAD]);
+    /// ```
+    fn read_val<T: Castable>(&self, offset: usize) -> Result<T, Error> {
+        let size = std::mem::size_of::<T>();
+        let bytes = self.read(offset, size).map_err(typed_operation::<T>("read_val", Some(offset), size))?;
+        Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+    }
+    /// Read a copy of *count* objects of type *T* starting at the given *offset*.
+    ///
+    /// The offset need not be aligned for type *T*. See [`Buffer::read_val`](Buffer::read_val).
+    fn read_vals<T: Castable>(&self, offset: usize, count: usize) -> Result<Vec<T>, Error> {
+        let size = std::mem::size_of::<T>();
+        let op = typed_operation::<T>("read_vals", Some(offset), size.saturating_mul(count));
+        let total = match size.checked_mul(count) {
+            Some(total) => total,
+            None => return Err(op(Error::Overflow(count))),
+        };
+        let bytes = self.read(offset, total).map_err(op)?;
+
+        Ok((0..count)
+           .map(|i| unsafe { std::ptr::read_unaligned(bytes.as_ptr().add(i * size) as *const T) })
+           .collect())
+    }
+    /// Get a [`TypedView`](TypedView) of *count* consecutive objects of type *T* at the given *offset*.
+    ///
+    /// Unlike [`Buffer::get_slice_ref`](Buffer::get_slice_ref), the offset need not be aligned for type *T*,
+    /// because elements are copied out of the buffer when accessed. Returns an [`Error::OutOfBounds`](Error::OutOfBounds)
+    /// error if the view runs out of boundaries.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hex;
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(&hex::decode("00f00dbeef1deadead").unwrap());
+    /// let view = buffer.view::<u16>(1, 4).unwrap();
+    /// assert_eq!(view.len(), 4);
+    /// assert_eq!(view.get(1), Some(0xEFBE));
+    /// assert_eq!(view.iter().collect::<Vec<u16>>(), [0x0DF0, 0xEFBE, 0xEA1D, 0xADDE]);
+    /// ```
+    fn view<T: Castable>(&self, offset: usize, count: usize) -> Result<TypedView<'_, T>, Error> {
+        self.view_strided::<T>(offset, count, std::mem::size_of::<T>())
+    }
+    /// Get a [`TypedView`](TypedView) of *count* objects of type *T* at the given *offset*, with each object
+    /// starting *stride* bytes after the previous one.
+    ///
+    /// Returns an [`Error::SizeMismatch`](Error::SizeMismatch) error if the stride is smaller than the size of *T*.
+    /// See [`Buffer::view`](Buffer::view).
+    ///
+    /// # Example
+    /// ```rust
+    /// use hex;
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// // a table of three 6-byte entries, of which we only care about the leading dword
+    /// let buffer = VecBuffer::from_data(&hex::decode("010000009999020000009999030000009999").unwrap());
+    /// let view = buffer.view_strided::<u32>(0, 3, 6).unwrap();
+    /// assert_eq!(view.to_vec(), [1, 2, 3]);
+    /// ```
+    fn view_strided<T: Castable>(&self, offset: usize, count: usize, stride: usize) -> Result<TypedView<'_, T>, Error> {
+        let op = typed_operation::<T>("view", Some(offset), count.saturating_mul(stride));
+
+        if offset > self.len() { return Err(op(Error::OutOfBounds(self.len(),offset))); }
+
+        TypedView::<T>::new(&self.as_slice()[offset..], count, stride).map_err(op)
+    }
+    /// Search for the given [`u8`](u8) [slice](slice) *data* within the given buffer.
+    ///
+    /// On success, this returns an iterator to all found offsets which match the given search term.
+    /// Typically, the error returned is an [`Error::OutOfBounds`](Error::OutOfBounds) error, when the search
+    /// term exceeds the size of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hex;
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(&hex::decode("beefbeefb33fbeefbeef").unwrap());
+    /// let search = buffer.search(&[0xBE, 0xEF]);
+    /// assert!(search.is_ok());
+    ///
+    /// let mut results = search.unwrap();
+    /// assert_eq!(results.next().unwrap(), 0);
+    /// assert_eq!(results.next().unwrap(), 2);
+    /// assert_eq!(results.next().unwrap(), 6);
+    /// assert_eq!(results.next().unwrap(), 8);
+    /// assert!(results.next().is_none());
+    ///
+    /// // alternatively, you can snatch up the search results into a Vec
+    /// let search_results = buffer.search(&[0xBE, 0xEF]).unwrap().collect::<Vec<usize>>();
+    /// assert_eq!(search_results, [0,2,6,8]);
+    /// ```
+    fn search<B: AsRef<[u8]>>(&self, data: B) -> Result<BufferSearchIter, Error> {
+        let data = data.as_ref();
+        BufferSearchIter::new(self.as_slice(), data).map_err(operation("search", None, data.len()))
+    }
+    /// Search for the following reference of type *T*. This converts the object into a [`u8`](u8) [slice](slice).
+    /// See [`Buffer::search`](Buffer::search).
+    fn search_ref<T: Castable>(&self, data: &T) -> Result<BufferSearchIter, Error> {
+        let op = typed_operation::<T>("search_ref", None, std::mem::size_of::<T>());
+        let bytes = ref_to_bytes::<T>(data).map_err(op)?;
+        self.search(bytes).map_err(op)
+    }
+    /// Search for the following slice reference of type *T*. This converts the slice into a [`u8`](u8) [slice](slice).
+    /// See [`Buffer::search`](Buffer::search).
+    fn search_slice_ref<T: Castable>(&self, data: &[T]) -> Result<BufferSearchIter, Error> {
+        let op = typed_operation::<T>("search_slice_ref", None, std::mem::size_of_val(data));
+        let bytes = slice_ref_to_bytes::<T>(data).map_err(op)?;
+        self.search(bytes).map_err(op)
+    }
+    /// Return a search iterator for a dynamic byte pattern within the binary.
+    ///
+    /// This function uses an [`Option<u8>`](Option) wrapper to represent wildcards in the search terms.
+    /// For example, a search term for a four-byte sequence wrapped in `0xFF`, use this:
+    ///
+    /// ```rust
+    /// let search = vec![Some(0xFFu8), None, None, None, None, Some(0xFFu8)];
+    /// ```
+    ///
+    /// For more information about searching, see [`Buffer::search`](Buffer::search).
+    fn search_dynamic<'a, B: AsRef<[Option<u8>]>>(&'a self, data: B) -> Result<BufferSearchDynamicIter<'a>, Error> {
+        let length = data.as_ref().len();
+        BufferSearchDynamicIter::new(self.as_slice(), data).map_err(operation("search_dynamic", None, length))
+    }
+    /// Search the buffer for the given [`u8`](u8) [slice](slice) on multiple threads, returning the sorted offsets
+    /// of every match.
+    ///
+    /// The buffer is split into chunks which overlap by the length of the search term, so matches crossing a
+    /// chunk boundary are found exactly once. Unlike [`Buffer::search`](Buffer::search), overlapping matches are
+    /// all returned, the same as [`Buffer::search_dynamic`](Buffer::search_dynamic), so the result doesn't depend
+    /// on how the buffer was split.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the search term is longer than the buffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data([0xAAu8; 0x40000]);
+    /// let matches = buffer.par_search([0xAA, 0xAA]).unwrap();
+    /// assert_eq!(matches.len(), 0x3FFFF);
+    /// assert_eq!(matches[0x10000], 0x10000);
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_search<B: AsRef<[u8]>>(&self, data: B) -> Result<Vec<usize>, Error> {
+        let data = data.as_ref();
+        par_search(self.as_slice(), data).map_err(operation("par_search", None, data.len()))
+    }
+    /// Search the buffer for the given dynamic search term on multiple threads, returning the sorted offsets of
+    /// every match. See [`Buffer::search_dynamic`](Buffer::search_dynamic) and [`Buffer::par_search`](Buffer::par_search).
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the search term is longer than the buffer and
+    /// an [`Error::SearchMatchesEverything`](Error::SearchMatchesEverything) error if it's made of wildcards only.
+    #[cfg(feature = "rayon")]
+    fn par_search_dynamic<B: AsRef<[Option<u8>]>>(&self, data: B) -> Result<Vec<usize>, Error> {
+        let data = data.as_ref();
+        par_search_dynamic(self.as_slice(), data).map_err(operation("par_search_dynamic", None, data.len()))
+    }
+    /// Search the buffer for several [`u8`](u8) [slices](slice) at once on multiple threads, in a single pass over
+    /// the buffer. Returns `(offset, index)` pairs, where *index* is the position of the matching term within *terms*,
+    /// sorted by offset and then by index. See [`Buffer::par_search`](Buffer::par_search).
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if any search term is longer than the buffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(b"MZ\x90\x00PE\x00\x00MZ");
+    /// let matches = buffer.par_search_many(&[&b"PE\x00\x00"[..], &b"MZ"[..]]).unwrap();
+    /// assert_eq!(matches, [(0, 1), (4, 0), (8, 1)]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_search_many<B: AsRef<[u8]>>(&self, terms: &[B]) -> Result<Vec<(usize, usize)>, Error> {
+        par_search_many(self.as_slice(), terms).map_err(operation("par_search_many", None, terms.len()))
+    }
+    /// Check if this buffer contains the following [`u8`](u8) [slice](slice) sequence.
+    fn contains<B: AsRef<[u8]>>(&self, data: B) -> bool {
+        let buf = data.as_ref();
+
+        if buf.len() > self.len() { return false; }
+
+        let mut offset = 0usize;
+
+        for i in 0..self.len() {
+            if offset >= buf.len() { break; }
+
+            if *self.get(i).unwrap() != buf[offset] { offset = 0; continue; }
+            else { offset += 1; }
+        }
+
+        offset == buf.len()
+    }
+    /// Check if this buffer contains the following object of type *T*.
+    fn contains_ref<T: Castable>(&self, data: &T) -> Result<bool, Error> {
+        let bytes = ref_to_bytes::<T>(data).map_err(typed_operation::<T>("contains_ref", None, std::mem::size_of::<T>()))?;
+        Ok(self.contains(bytes))
+    }
+    /// Check if this buffer contains the following slice of type *T*.
+    fn contains_slice_ref<T: Castable>(&self, data: &[T]) -> Result<bool, Error> {
+        let bytes = slice_ref_to_bytes::<T>(data).map_err(typed_operation::<T>("contains_slice_ref", None, std::mem::size_of_val(data)))?;
+        Ok(self.contains(bytes))
+    }
+    /// Check if this buffer starts with the byte sequence *needle*. See [`slice::starts_with`](slice::starts_with).
+    fn starts_with<B: AsRef<[u8]>>(&self, needle: B) -> bool {
+        self.as_slice().starts_with(needle.as_ref())
+    }
+    /// Check if this buffer ends with the byte sequence *needle*. See [`slice::ends_with`](slice::ends_with).
+    fn ends_with<B: AsRef<[u8]>>(&self, needle: B) -> bool {
+        self.as_slice().ends_with(needle.as_ref())
+    }
+    /// Check if this buffer is ASCII. See [`slice::is_ascii`](slice::is_ascii).
+    fn is_ascii(&self) -> bool {
+        self.as_slice().is_ascii()
+    }
+    /// Check if this buffer is equal while ignoring case of letters. See [`slice::eq_ignore_ascii_case`](slice::eq_ignore_ascii_case).
+    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        self.as_slice().eq_ignore_ascii_case(other)
+    }
+    /// Creates a new `Buffer` object by repeating the current buffer *n* times. See [`slice::repeat`](slice::repeat).
+    fn repeat(&self, n: usize) -> Vec<u8> {
+        self.as_slice().repeat(n)
+    }
+    /// Calculate the checksum of the given *range* of the buffer with the given [`Algorithm`](Algorithm).
+    ///
+    /// 16-bit and 32-bit checksums are zero-extended to a [`u64`](u64). Returns an
+    /// [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    /// To checksum multiple ranges, see [`Checksum`](Checksum).
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Algorithm, Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data(b"Wikipedia");
+    /// assert_eq!(buffer.checksum(.., Algorithm::Adler32).unwrap(), 0x11E60398);
+    /// assert_eq!(buffer.checksum(0..4, Algorithm::Fnv1a32).unwrap(), 0x8B497DC1);
+    /// assert!(buffer.checksum(0..10, Algorithm::Crc32).is_err());
+    /// ```
+    fn checksum<R: std::ops::RangeBounds<usize>>(&self, range: R, algorithm: Algorithm) -> Result<u64, Error> {
+        let op = range_operation("checksum", self.len(), &range);
+        let mut checksum = Checksum::new(algorithm);
+        checksum.update_buffer(self, range).map_err(op)?;
+        Ok(checksum.finish())
+    }
+    /// Count the occurrences of each byte value within the given *range* of the buffer.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    fn byte_histogram<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Result<[usize; 256], Error> {
+        let op = range_operation("byte_histogram", self.len(), &range);
+        let range = resolve_range(self.len(), range).map_err(op)?;
+        let mut histogram = [0usize; 256];
+
+        for byte in &self.as_slice()[range] {
+            histogram[*byte as usize] += 1;
+        }
+
+        Ok(histogram)
+    }
+    /// Calculate the Shannon entropy of the given *range* of the buffer, in bits per byte.
+    ///
+    /// The result ranges from `0.0` to `8.0`, where high values typically indicate compressed or
+    /// encrypted data. Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is
+    /// out of bounds of the buffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data((0..=255).collect::<Vec<u8>>());
+    /// assert_eq!(buffer.entropy(..).unwrap(), 8.0);
+    /// assert_eq!(buffer.entropy(0..1).unwrap(), 0.0);
+    /// assert_eq!(buffer.entropy(0..4).unwrap(), 2.0);
+    /// ```
+    fn entropy<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Result<f64, Error> {
+        let op = range_operation("entropy", self.len(), &range);
+        let histogram = self.byte_histogram(range).map_err(op)?;
+        Ok(histogram_entropy(&histogram))
+    }
+    /// Return an iterator ([`EntropyWindows`](EntropyWindows)) of the entropy of each *window*-sized
+    /// region of the buffer, advancing *step* bytes at a time.
+    ///
+    /// Each item is an `(offset, entropy)` pair. Only full windows are yielded, so a buffer smaller
+    /// than the window yields nothing.
+    ///
+    /// # Panics
+    /// Panics if *window* or *step* is zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, BufferMut, VecBuffer};
+    ///
+    /// let mut buffer = VecBuffer::with_initial_size(0x100);
+    /// assert!(buffer.write(0x80, (0..0x80).collect::<Vec<u8>>()).is_ok());
+    ///
+    /// let windows = buffer.windows_entropy(0x80, 0x40).collect::<Vec<(usize, f64)>>();
+    /// assert_eq!(windows.len(), 3);
+    /// assert_eq!(windows[0], (0x0, 0.0));
+    /// assert_eq!(windows[2], (0x80, 7.0));
+    /// ```
+    fn windows_entropy(&self, window: usize, step: usize) -> EntropyWindows<'_> {
+        EntropyWindows::new(self.as_slice(), window, step)
+    }
+    /// Calculate the SHA-1 hash of the given *range* of the buffer.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    #[cfg(feature = "sha1")]
+    fn sha1<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Result<[u8; 20], Error> {
+        use sha1::Digest;
+
+        let op = range_operation("sha1", self.len(), &range);
+        let range = resolve_range(self.len(), range).map_err(op)?;
+        Ok(sha1::Sha1::digest(&self.as_slice()[range]).into())
+    }
+    /// Calculate the SHA-256 hash of the given *range* of the buffer.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    #[cfg(feature = "sha2")]
+    fn sha256<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Result<[u8; 32], Error> {
+        use sha2::Digest;
 
-        if offset+size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+size));
-        }
+        let op = range_operation("sha256", self.len(), &range);
+        let range = resolve_range(self.len(), range).map_err(op)?;
+        Ok(sha2::Sha256::digest(&self.as_slice()[range]).into())
+    }
+    /// Calculate the MD5 hash of the given *range* of the buffer.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    #[cfg(feature = "md5")]
+    fn md5<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Result<[u8; 16], Error> {
+        use md5::Digest;
 
-        Ok(&*(ptr as *const T))
+        let op = range_operation("md5", self.len(), &range);
+        let range = resolve_range(self.len(), range).map_err(op)?;
+        Ok(md5::Md5::digest(&self.as_slice()[range]).into())
     }
-    /// Get a reference regardless of potential alignment issues.
+}
+
+/// The trait by which all mutable buffer objects are derived.
+///
+/// This extends [`Buffer`](Buffer) with everything that writes to the underlying data. Buffers over
+/// read-only memory, such as [`PtrBuffer`](crate::PtrBuffer), only implement [`Buffer`](Buffer), so
+/// writing through them doesn't compile.
+pub trait BufferMut: Buffer {
+    /// Get the `Buffer` object as a mutable pointer.
+    fn as_mut_ptr(&mut self) -> *mut u8;
+    /// Get the `Buffer` object as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [u8];
+
+    /// Get a mutable pointer range of this buffer. See [slice::as_mut_ptr_range](slice::as_mut_ptr_range) for more details.
+    fn as_mut_ptr_range(&mut self) -> std::ops::Range<*mut u8> {
+        std::ops::Range::<*mut u8> { start: self.as_mut_ptr(), end: self.eob() as *mut u8 }
+    }
+    /// Convert an *offset* to a mutable [`u8`](u8) pointer.
     ///
-    /// It is not recommended you use this function if you're unaware of the alignment
-    /// situation of your processor or data. See
-    /// [`Buffer::get_ref_unaligned`](Buffer::get_ref_unaligned) for more details.
-    unsafe fn force_get_ref<T: Castable>(&self, offset: usize) -> Result<&T, Error> {
-        match self.get_ref::<T>(offset) {
-            Ok(ref_data) => Ok(ref_data),
-            Err(err) => {
-                if let Error::BadAlignment(_,_) = err { self.get_ref_unaligned::<T>(offset) }
-                else { Err(err) }
-            },
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset is out of bounds
+    /// of the buffer.
+    fn offset_to_mut_ptr(&mut self, offset: usize) -> Result<*mut u8, Error> {
+        if offset >= self.len() {
+            return Err(Error::OutOfBounds(self.len(),offset));
         }
+
+        unsafe { Ok(self.as_mut_ptr().add(offset)) }
+    }
+    /// Swap two bytes at the given offsets. This panics if the offsets are out of bounds. See [`slice::swap`](slice::swap)
+    /// for more details.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b);
+    }
+    /// Reverse the buffer. See [`slice::reverse`](slice::reverse) for more details.
+    fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+    /// Return a mutable iterator object ([`BufferIterMut`](BufferIterMut)) into the buffer.
+    fn iter_mut(&mut self) -> BufferIterMut<'_> {
+        BufferIterMut { buffer: self.as_mut_slice(), index: 0 }
+    }
+    /// Get the given byte or range of bytes from the buffer as mutable. See [`slice::get_mut`](slice::get_mut) for more details.
+    fn get_mut<I: std::slice::SliceIndex<[u8]>>(&mut self, index: I) -> Option<&mut I::Output> {
+        self.as_mut_slice().get_mut(index)
     }
     /// Get a mutable reference to a given object within the buffer. See [`Buffer::get_ref`](Buffer::get_ref).
     fn get_mut_ref<T: Castable>(&mut self, offset: usize) -> Result<&mut T, Error> {
         let size = std::mem::size_of::<T>();
-        let bytes = self.get_mut_slice_ref::<u8>(offset, size)?;
-        bytes_to_mut_ref::<T>(bytes)
+        let op = typed_operation::<T>("get_mut_ref", Some(offset), size);
+        let bytes = self.get_mut_slice_ref::<u8>(offset, size).map_err(op)?;
+        bytes_to_mut_ref::<T>(bytes).map_err(op)
     }
     /// Get a mutable reference to a given object within the buffer, but in an unaligned way.
     ///
@@ -193,34 +759,38 @@ pub trait Buffer {
     /// This is an unsafe function because it gets a reference that is not aligned to a proper boundary, which
     /// can trigger undefined behavior on some processors. If you're unsure of the alignment situation on your
     /// target processor, or unsure of the alignment situation in your data, it's best to use
-    /// [`Buffer::get_mut_ref`](Buffer::get_mut_ref) instead.
+    /// [`BufferMut::get_mut_ref`](BufferMut::get_mut_ref) instead.
     unsafe fn get_mut_ref_unaligned<T>(&mut self, offset: usize) -> Result<&mut T, Error> {
-        let ptr = self.offset_to_mut_ptr(offset)?;
         let size = std::mem::size_of::<T>();
+        let op = typed_operation::<T>("get_mut_ref_unaligned", Some(offset), size);
+        let ptr = self.offset_to_mut_ptr(offset).map_err(op)?;
+        let end = span_end::<T>(offset, 1).map_err(op)?;
 
-        if offset+size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+size));
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
         }
 
         Ok(&mut *(ptr as *const T as *mut T))
     }
     /// Get a mutable reference regardless of potential alignment issues.
     ///
+    /// # Safety
     /// It is not recommended you use this function if you're unaware of the alignment
     /// situation of your processor or data. See
-    /// [`Buffer::get_mut_ref_unaligned`](Buffer::get_mut_ref_unaligned) for more details.
+    /// [`BufferMut::get_mut_ref_unaligned`](BufferMut::get_mut_ref_unaligned) for more details.
     unsafe fn force_get_mut_ref<T: Castable>(&mut self, offset: usize) -> Result<&mut T, Error> {
         // I'm unsure why the borrow checker is annoyed at this code, attempting to go out
         // of scope of the returned error (or even explicitly dropping it) still doesn't let
         // me borrow again, so just do some pointer magic to make a new reference. if you
         // know why this is causing a borrow issue and how to fix it please file a ticket on GitHub.
         let second_ref = &mut *(self as *mut Self);
+        let op = typed_operation::<T>("force_get_mut_ref", Some(offset), std::mem::size_of::<T>());
         
         match self.get_mut_ref::<T>(offset) {
             Ok(ref_data) => Ok(ref_data),
             Err(err) => {
-                if let Error::BadAlignment(_,_) = err { second_ref.get_mut_ref_unaligned::<T>(offset) }
-                else { Err(err) }
+                if let Error::BadAlignment(_,_) = err.root() { second_ref.get_mut_ref_unaligned::<T>(offset).map_err(op) }
+                else { Err(op(err)) }
             },
         }
     }
@@ -229,139 +799,80 @@ pub trait Buffer {
     /// Returns an [`Error::InvalidPointer`](Error::InvalidPointer) error if the reference did not
     /// originate from this buffer.
     fn make_mut_ref<T: Castable>(&mut self, data: &T) -> Result<&mut T, Error> {
-        let offset = self.ref_to_offset(data)?;
-        self.get_mut_ref::<T>(offset)
+        let op = typed_operation::<T>("make_mut_ref", None, std::mem::size_of::<T>());
+        let offset = self.ref_to_offset(data).map_err(op)?;
+        self.get_mut_ref::<T>(offset).map_err(op)
     }
     /// Convert a given reference to a mutable reference without alignment guarantees.
     ///
+    /// # Safety
     /// You should not do this unless you know your alignment situation. See
-    /// [`Buffer::get_mut_ref_unaligned`](Buffer::get_mut_ref_unaligned) for an explanation as to why.
+    /// [`BufferMut::get_mut_ref_unaligned`](BufferMut::get_mut_ref_unaligned) for an explanation as to why.
     unsafe fn make_mut_ref_unaligned<T>(&mut self, data: &T) -> Result<&mut T, Error> {
-        let offset = self.ref_to_offset(data)?;
-        self.get_mut_ref_unaligned::<T>(offset)
+        let op = typed_operation::<T>("make_mut_ref_unaligned", None, std::mem::size_of::<T>());
+        let offset = self.ref_to_offset(data).map_err(op)?;
+        self.get_mut_ref_unaligned::<T>(offset).map_err(op)
     }
     /// Convert an object to a mutable reference regardless of potential alignment issues.
     ///
+    /// # Safety
     /// You should not do this unless you know your alignment situation. See
-    /// [`Buffer::make_mut_ref_unaligned`](Buffer::make_mut_ref_unaligned) for an explanation as to why.
+    /// [`BufferMut::make_mut_ref_unaligned`](BufferMut::make_mut_ref_unaligned) for an explanation as to why.
     unsafe fn force_make_mut_ref<T: Castable>(&mut self, data: &T) -> Result<&mut T, Error> {
         // I'm unsure why the borrow checker is annoyed at this code, attempting to go out
         // of scope of the returned error (or even explicitly dropping it) still doesn't let
         // me borrow again, so just do some pointer magic to make a new reference. if you
         // know why this is causing a borrow issue and how to fix it please file a ticket on GitHub.
         let second_ref = &mut *(self as *mut Self);
-        let offset = self.ref_to_offset(data)?;
+        let op = typed_operation::<T>("force_make_mut_ref", None, std::mem::size_of::<T>());
+        let offset = self.ref_to_offset(data).map_err(op)?;
         
         match self.get_mut_ref::<T>(offset) {
             Ok(ref_data) => Ok(ref_data),
             Err(err) => {
-                if let Error::BadAlignment(_,_) = err { second_ref.get_mut_ref_unaligned::<T>(offset) }
-                else { Err(err) }
-            },
-        }
-    }
-    /// Gets a slice reference of type *T* at the given *offset* with the given *size*.
-    ///
-    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset or the
-    /// offset plus its size goes out of bounds of the buffer and returns
-    /// [`Error::BadAlignment`](Error::BadAlignment) if the acquired slice is not aligned
-    /// on the alignment boundary required by type *T*.
-    ///
-    /// # Example
-    /// ```rust
-    /// use hex;
-    /// use pkbuffer::{Buffer, VecBuffer};
-    ///
-    /// let buffer = VecBuffer::from_data(&hex::decode("f00dbeef1deadead").unwrap());
-    ///
-    /// let slice = buffer.get_slice_ref::<u16>(0, 4);
-    /// assert!(slice.is_ok());
-    /// assert_eq!(slice.unwrap(), [0x0DF0, 0xEFBE, 0xEA1D, 0xADDE]);
-    /// ```
-    fn get_slice_ref<T: Castable>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
-        let ptr = self.offset_to_ptr(offset)?;
-        let real_size = std::mem::size_of::<T>() * size;
-                
-        if offset+real_size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+real_size));
-        }
-
-        let alignment = std::mem::align_of::<T>();
-
-        if (ptr as usize) % alignment != 0 {
-            return Err(Error::BadAlignment(alignment, (ptr as usize) % alignment));
-        }
-
-        unsafe { Ok(std::slice::from_raw_parts(ptr as *const T, size)) }
-    }
-    /// Gets a slice ref of type *T* at the given *offset* regardless of potential alignment
-    /// issues.
-    ///
-    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the offset or the offset
-    /// plus its size go out of bounds of the buffer.
-    ///
-    /// # Safety
-    /// This is an unsafe function because it gets a slice reference that is not aligned to a proper boundary, which
-    /// can trigger undefined behavior on some processors. If you're unsure of the alignment situation on your
-    /// target processor, or unsure of the alignment situation in your data, it's best to use
-    /// [`Buffer::get_slice_ref`](Buffer::get_slice_ref) instead.
-    unsafe fn get_slice_ref_unaligned<T>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
-        let ptr = self.offset_to_ptr(offset)?;
-        let type_size = std::mem::size_of::<T>();
-        let slice_end = offset + (size * type_size);
-
-        if slice_end > self.len() {
-            return Err(Error::OutOfBounds(self.len(), slice_end));
-        }
-
-        Ok(std::slice::from_raw_parts(ptr as *const T, size))
-    }
-    /// Get a slice reference regardless of potential alignment issues.
-    ///
-    /// It is not recommended you use this function if you're unaware of the alignment
-    /// situation of your processor or data. See [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned)
-    /// for more details.
-    unsafe fn force_get_slice_ref<T: Castable>(&self, offset: usize, size: usize) -> Result<&[T], Error> {
-        match self.get_slice_ref::<T>(offset, size) {
-            Ok(ref_data) => Ok(ref_data),
-            Err(err) => {
-                if let Error::BadAlignment(_,_) = err { self.get_slice_ref_unaligned::<T>(offset, size) }
-                else { Err(err) }
+                if let Error::BadAlignment(_,_) = err.root() { second_ref.get_mut_ref_unaligned::<T>(offset).map_err(op) }
+                else { Err(op(err)) }
             },
         }
     }
     /// Gets a mutable slice reference of type *T* at the given *offset* with the given *size*.
     /// See [`Buffer::get_slice_ref`](Buffer::get_slice_ref).
     fn get_mut_slice_ref<T: Castable>(&mut self, offset: usize, size: usize) -> Result<&mut [T], Error> {
-        let ptr = self.offset_to_mut_ptr(offset)?;
-        let real_size = std::mem::size_of::<T>() * size;
-                
-        if offset+real_size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+real_size));
+        let op = typed_operation::<T>("get_mut_slice_ref", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
+        let ptr = self.offset_to_mut_ptr(offset).map_err(op)?;
+        let end = span_end::<T>(offset, size).map_err(op)?;
+
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
         }
 
         let alignment = std::mem::align_of::<T>();
 
         if (ptr as usize) % alignment != 0 {
-            return Err(Error::BadAlignment(alignment, offset % alignment));
+            return Err(op(Error::BadAlignment(alignment, offset % alignment)));
         }
 
         unsafe { Ok(std::slice::from_raw_parts_mut(ptr as *mut T, size)) }
     }
     /// Gets a mutable slice reference of type *T* at the given *offset* with the given *size*,
-    /// but without alignment checking. See [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned).
+    /// but without alignment checking.
+    ///
+    /// # Safety
+    /// See [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned).
     unsafe fn get_mut_slice_ref_unaligned<T>(&mut self, offset: usize, size: usize) -> Result<&mut [T], Error> {
-        let ptr = self.offset_to_mut_ptr(offset)?;
-        let real_size = std::mem::size_of::<T>() * size;
-                
-        if offset+real_size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+real_size));
+        let op = typed_operation::<T>("get_mut_slice_ref_unaligned", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
+        let ptr = self.offset_to_mut_ptr(offset).map_err(op)?;
+        let end = span_end::<T>(offset, size).map_err(op)?;
+
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
         }
 
         Ok(std::slice::from_raw_parts_mut(ptr as *mut T, size))
     }
     /// Get a mutable slice reference regardless of potential alignment issues.
     ///
+    /// # Safety
     /// It is not recommended you use this function if you're unaware of the alignment
     /// situation of your processor or data. See
     /// [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned) for more details.
@@ -371,12 +882,13 @@ pub trait Buffer {
         // me borrow again, so just do some pointer magic to make a new reference. if you
         // know why this is causing a borrow issue and how to fix it please file a ticket on GitHub.
         let second_ref = &mut *(self as *mut Self);
+        let op = typed_operation::<T>("force_get_mut_slice_ref", Some(offset), std::mem::size_of::<T>().saturating_mul(size));
 
         match self.get_mut_slice_ref::<T>(offset, size) {
             Ok(ref_data) => Ok(ref_data),
             Err(err) => {
-                if let Error::BadAlignment(_,_) = err { second_ref.get_mut_slice_ref_unaligned::<T>(offset, size) }
-                else { Err(err) }
+                if let Error::BadAlignment(_,_) = err.root() { second_ref.get_mut_slice_ref_unaligned::<T>(offset, size).map_err(op) }
+                else { Err(op(err)) }
             },
         }
     }
@@ -385,19 +897,23 @@ pub trait Buffer {
     /// Returns an [`Error::InvalidPointer`](Error::InvalidPointer) error if the reference did not
     /// originate from this buffer.
     fn make_mut_slice_ref<T: Castable>(&mut self, data: &[T]) -> Result<&mut [T], Error> {
-        let offset = self.ptr_to_offset(data.as_ptr() as *const u8)?;
-        self.get_mut_slice_ref::<T>(offset, data.len())
+        let op = typed_operation::<T>("make_mut_slice_ref", None, std::mem::size_of_val(data));
+        let offset = self.ptr_to_offset(data.as_ptr() as *const u8).map_err(op)?;
+        self.get_mut_slice_ref::<T>(offset, data.len()).map_err(op)
     }
     /// Convert a given slice reference to a mutable slice reference without alignment guarantees.
     ///
+    /// # Safety
     /// You should not do this unless you know your alignment situation. See
     /// [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned) for an explanation as to why.
     unsafe fn make_mut_slice_ref_unaligned<T>(&mut self, data: &[T]) -> Result<&mut [T], Error> {
-        let offset = self.slice_ref_to_offset(data)?;
-        self.get_mut_slice_ref_unaligned::<T>(offset, data.len())
+        let op = typed_operation::<T>("make_mut_slice_ref_unaligned", None, std::mem::size_of_val(data));
+        let offset = self.slice_ref_to_offset(data).map_err(op)?;
+        self.get_mut_slice_ref_unaligned::<T>(offset, data.len()).map_err(op)
     }
     /// Convert an object to a mutable reference regardless of potential alignment issues.
     ///
+    /// # Safety
     /// You should not do this unless you know your alignment situation. See
     /// [`Buffer::get_slice_ref_unaligned`](Buffer::get_slice_ref_unaligned) for an explanation as to why.
     unsafe fn force_make_mut_slice_ref<T: Castable>(&mut self, data: &[T]) -> Result<&mut [T], Error> {
@@ -406,40 +922,38 @@ pub trait Buffer {
         // me borrow again, so just do some pointer magic to make a new reference. if you
         // know why this is causing a borrow issue and how to fix it please file a ticket on GitHub.
         let second_ref = &mut *(self as *mut Self);
-        let offset = self.slice_ref_to_offset(data)?;
+        let op = typed_operation::<T>("force_make_mut_slice_ref", None, std::mem::size_of_val(data));
+        let offset = self.slice_ref_to_offset(data).map_err(op)?;
         
         match self.get_mut_slice_ref::<T>(offset, data.len()) {
             Ok(ref_data) => Ok(ref_data),
             Err(err) => {
-                if let Error::BadAlignment(_,_) = err { second_ref.get_mut_slice_ref_unaligned::<T>(offset, data.len()) }
-                else { Err(err) }
+                if let Error::BadAlignment(_,_) = err.root() { second_ref.get_mut_slice_ref_unaligned::<T>(offset, data.len()).map_err(op) }
+                else { Err(op(err)) }
             },
         }
     }
-    /// Read an arbitrary *size* amount of bytes from the given *offset*.
-    ///
-    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the read runs out of boundaries.
-    fn read(&self, offset: usize, size: usize) -> Result<&[u8], Error> {
-        self.get_slice_ref::<u8>(offset, size)
-    }
     /// Read an arbitrary *size* amount of bytes from the given *offset*, but mutable.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the read runs out of boundaries.
     fn read_mut(&mut self, offset: usize, size: usize) -> Result<&mut [u8], Error> {
-        self.get_mut_slice_ref::<u8>(offset, size)
+        self.get_mut_slice_ref::<u8>(offset, size).map_err(operation("read_mut", Some(offset), size))
     }
     /// Write an arbitrary [`u8`](u8) [slice](slice) to the given *offset*.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries
-    /// of the buffer.
+    /// of the buffer, and an [`Error::Overflow`](Error::Overflow) error if the offset plus the size of the
+    /// data doesn't fit in a `usize`.
     fn write<B: AsRef<[u8]>>(&mut self, offset: usize, data: B) -> Result<(), Error> {
         let buf = data.as_ref();
         let from_ptr = buf.as_ptr();
-        let to_ptr = self.offset_to_mut_ptr(offset)?;
         let size = buf.len();
+        let op = operation("write", Some(offset), size);
+        let to_ptr = self.offset_to_mut_ptr(offset).map_err(op)?;
+        let end = span_end::<u8>(offset, size).map_err(op)?;
 
-        if offset+size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+size));
+        if end > self.len() {
+            return Err(op(Error::OutOfBounds(self.len(),end)));
         }
 
         unsafe { std::ptr::copy(from_ptr, to_ptr, size); }
@@ -450,149 +964,158 @@ pub trait Buffer {
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn write_ref<T: Castable>(&mut self, offset: usize, data: &T) -> Result<(), Error> {
-        let bytes = ref_to_bytes::<T>(data)?;
-        self.write(offset, bytes)
+        let op = typed_operation::<T>("write_ref", Some(offset), std::mem::size_of::<T>());
+        let bytes = ref_to_bytes::<T>(data).map_err(op)?;
+        self.write(offset, bytes).map_err(op)
     }
     /// Write a given slice object of type *T* to the given buffer at the given *offset*.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn write_slice_ref<T: Castable>(&mut self, offset: usize, data: &[T]) -> Result<(), Error> {
-        let bytes = slice_ref_to_bytes::<T>(data)?;
-        self.write(offset, bytes)
+        let op = typed_operation::<T>("write_slice_ref", Some(offset), std::mem::size_of_val(data));
+        let bytes = slice_ref_to_bytes::<T>(data).map_err(op)?;
+        self.write(offset, bytes).map_err(op)
+    }
+    /// Write a given object of type *T* by value to the given *offset*.
+    ///
+    /// The offset need not be aligned for type *T*. Returns an [`Error::OutOfBounds`](Error::OutOfBounds)
+    /// error if the write runs out of boundaries.
+    fn write_val<T: Castable>(&mut self, offset: usize, data: T) -> Result<(), Error> {
+        self.write_ref::<T>(offset, &data).map_err(typed_operation::<T>("write_val", Some(offset), std::mem::size_of::<T>()))
+    }
+    /// Write the given slice of objects of type *T* to the given *offset*.
+    ///
+    /// The offset need not be aligned for type *T*. Returns an [`Error::OutOfBounds`](Error::OutOfBounds)
+    /// error if the write runs out of boundaries.
+    fn write_vals<T: Castable>(&mut self, offset: usize, data: &[T]) -> Result<(), Error> {
+        if data.is_empty() { return Ok(()); }
+
+        self.write_slice_ref::<T>(offset, data).map_err(typed_operation::<T>("write_vals", Some(offset), std::mem::size_of_val(data)))
+    }
+    /// Get a mutable [`TypedViewMut`](TypedViewMut) of *count* consecutive objects of type *T* at the given *offset*.
+    /// See [`Buffer::view`](Buffer::view).
+    fn view_mut<T: Castable>(&mut self, offset: usize, count: usize) -> Result<TypedViewMut<'_, T>, Error> {
+        self.view_mut_strided::<T>(offset, count, std::mem::size_of::<T>())
+    }
+    /// Get a mutable [`TypedViewMut`](TypedViewMut) of *count* objects of type *T* at the given *offset*, with
+    /// each object starting *stride* bytes after the previous one. See [`Buffer::view_strided`](Buffer::view_strided).
+    fn view_mut_strided<T: Castable>(&mut self, offset: usize, count: usize, stride: usize) -> Result<TypedViewMut<'_, T>, Error> {
+        let op = typed_operation::<T>("view_mut", Some(offset), count.saturating_mul(stride));
+
+        if offset > self.len() { return Err(op(Error::OutOfBounds(self.len(),offset))); }
+
+        TypedViewMut::<T>::new(&mut self.as_mut_slice()[offset..], count, stride).map_err(op)
+    }
+    /// Split the buffer into two non-overlapping [`SubBufferMut`](SubBufferMut) objects at the given splitpoint *mid*.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if this split goes out of bounds of the buffer.
+    fn split_regions_mut(&mut self, mid: usize) -> Result<(SubBufferMut<'_>, SubBufferMut<'_>), Error> {
+        let len = self.len();
+
+        if mid > len { return Err(operation("split_regions_mut", Some(mid), 0)(Error::OutOfBounds(len,mid))); }
+
+        let (left, right) = self.as_mut_slice().split_at_mut(mid);
+        Ok((SubBufferMut::new(left, 0), SubBufferMut::new(right, mid)))
+    }
+    /// Split the buffer into one [`SubBufferMut`](SubBufferMut) object per range in *ranges*, in the same order.
+    ///
+    /// The ranges need not be sorted or cover the whole buffer, but they can't overlap. Empty ranges never overlap
+    /// anything. Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if a range is out of bounds and an
+    /// [`Error::OverlappingRanges`](Error::OverlappingRanges) error naming the later of two overlapping ranges.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, BufferMut, VecBuffer};
+    ///
+    /// let mut buffer = VecBuffer::from_data([0u8; 8]);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for mut region in buffer.split_many_mut(&[4..8, 0..4]).unwrap() {
+    ///         scope.spawn(move || region.write_val::<u32>(0, region.offset() as u32).unwrap());
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(buffer.read_vals::<u32>(0, 2).unwrap(), [0, 4]);
+    /// assert!(buffer.split_many_mut(&[0..4, 2..6]).is_err());
+    /// ```
+    fn split_many_mut(&mut self, ranges: &[std::ops::Range<usize>]) -> Result<Vec<SubBufferMut<'_>>, Error> {
+        let len = self.len();
+        let op = operation("split_many_mut", None, len);
+        let mut order = Vec::<usize>::with_capacity(ranges.len());
+
+        for (index, range) in ranges.iter().enumerate() {
+            resolve_range(len, range.clone()).map_err(op)?;
+            if !range.is_empty() { order.push(index); }
+        }
+
+        order.sort_by_key(|&index| ranges[index].start);
+
+        for pair in order.windows(2) {
+            let (earlier, later) = (&ranges[pair[0]], &ranges[pair[1]]);
+
+            if later.start < earlier.end {
+                let (start, end) = if pair[0] < pair[1] { (later.start, later.end) } else { (earlier.start, earlier.end) };
+                return Err(op(Error::OverlappingRanges(start,end)));
+            }
+        }
+
+        let pointer = self.as_mut_ptr();
+
+        // SAFETY: every range is within the buffer and no two non-empty ranges overlap, so the slices
+        // are disjoint and all borrow from self
+        Ok(ranges.iter()
+           .map(|range| SubBufferMut::new(unsafe { std::slice::from_raw_parts_mut(pointer.add(range.start), range.len()) }, range.start))
+           .collect())
     }
     /// Start the buffer object with the given byte data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn start_with<B: AsRef<[u8]>>(&mut self, data: B) -> Result<(), Error> {
-        self.write(0, data)
+        let data = data.as_ref();
+        self.write(0, data).map_err(operation("start_with", Some(0), data.len()))
     }
     /// Start the buffer with the given reference data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn start_with_ref<T: Castable>(&mut self, data: &T) -> Result<(), Error> {
-        let bytes = ref_to_bytes::<T>(data)?;
-        self.start_with(bytes)
+        let op = typed_operation::<T>("start_with_ref", Some(0), std::mem::size_of::<T>());
+        let bytes = ref_to_bytes::<T>(data).map_err(op)?;
+        self.start_with(bytes).map_err(op)
     }
     /// Start the buffer with the given slice reference data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn start_with_slice_ref<T: Castable>(&mut self, data: &[T]) -> Result<(), Error> {
-        let bytes = slice_ref_to_bytes::<T>(data)?;
-        self.start_with(bytes)
+        let op = typed_operation::<T>("start_with_slice_ref", Some(0), std::mem::size_of_val(data));
+        let bytes = slice_ref_to_bytes::<T>(data).map_err(op)?;
+        self.start_with(bytes).map_err(op)
     }
     /// End the buffer object with the given byte data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn end_with<B: AsRef<[u8]>>(&mut self, data: B) -> Result<(), Error> {
         let buf = data.as_ref();
+        let op = operation("end_with", None, buf.len());
 
-        if buf.len() > self.len() { return Err(Error::OutOfBounds(self.len(),buf.len())); }
+        if buf.len() > self.len() { return Err(op(Error::OutOfBounds(self.len(),buf.len()))); }
         
-        self.write(self.len()-buf.len(), data)
+        self.write(self.len()-buf.len(), buf).map_err(op)
     }
     /// End the buffer with the given reference data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn end_with_ref<T: Castable>(&mut self, data: &T) -> Result<(), Error> {
-        let bytes = ref_to_bytes::<T>(data)?;
-        self.end_with(bytes)
+        let op = typed_operation::<T>("end_with_ref", None, std::mem::size_of::<T>());
+        let bytes = ref_to_bytes::<T>(data).map_err(op)?;
+        self.end_with(bytes).map_err(op)
     }
     /// End the buffer with the given slice reference data.
     ///
     /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the write runs out of boundaries.
     fn end_with_slice_ref<T: Castable>(&mut self, data: &[T]) -> Result<(), Error> {
-        let bytes = slice_ref_to_bytes::<T>(data)?;
-        self.end_with(bytes)
-    }
-    /// Search for the given [`u8`](u8) [slice](slice) *data* within the given buffer.
-    ///
-    /// On success, this returns an iterator to all found offsets which match the given search term.
-    /// Typically, the error returned is an [`Error::OutOfBounds`](Error::OutOfBounds) error, when the search
-    /// term exceeds the size of the buffer.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use hex;
-    /// use pkbuffer::{Buffer, VecBuffer};
-    ///
-    /// let buffer = VecBuffer::from_data(&hex::decode("beefbeefb33fbeefbeef").unwrap());
-    /// let search = buffer.search(&[0xBE, 0xEF]);
-    /// assert!(search.is_ok());
-    ///
-    /// let mut results = search.unwrap();
-    /// assert_eq!(results.next().unwrap(), 0);
-    /// assert_eq!(results.next().unwrap(), 2);
-    /// assert_eq!(results.next().unwrap(), 6);
-    /// assert_eq!(results.next().unwrap(), 8);
-    /// assert!(results.next().is_none());
-    ///
-    /// // alternatively, you can snatch up the search results into a Vec
-    /// let search_results = buffer.search(&[0xBE, 0xEF]).unwrap().collect::<Vec<usize>>();
-    /// assert_eq!(search_results, [0,2,6,8]);
-    /// ```
-    fn search<B: AsRef<[u8]>>(&self, data: B) -> Result<BufferSearchIter, Error> {
-        BufferSearchIter::new(self.as_slice(), data.as_ref())
-    }
-    /// Search for the following reference of type *T*. This converts the object into a [`u8`](u8) [slice](slice).
-    /// See [`Buffer::search`](Buffer::search).
-    fn search_ref<T: Castable>(&self, data: &T) -> Result<BufferSearchIter, Error> {
-        let bytes = ref_to_bytes::<T>(data)?;
-        self.search(bytes)
-    }
-    /// Search for the following slice reference of type *T*. This converts the slice into a [`u8`](u8) [slice](slice).
-    /// See [`Buffer::search`](Buffer::search).
-    fn search_slice_ref<T: Castable>(&self, data: &[T]) -> Result<BufferSearchIter, Error> {
-        let bytes = slice_ref_to_bytes::<T>(data)?;
-        self.search(bytes)
-    }
-    /// Return a search iterator for a dynamic byte pattern within the binary.
-    ///
-    /// This function uses an [`Option<u8>`](Option) wrapper to represent wildcards in the search terms.
-    /// For example, a search term for a four-byte sequence wrapped in `0xFF`, use this:
-    ///
-    /// ```rust
-    /// let search = vec![Some(0xFFu8), None, None, None, None, Some(0xFFu8)];
-    /// ```
-    ///
-    /// For more information about searching, see [`Buffer::search`](Buffer::search).
-    fn search_dynamic<'a, B: AsRef<[Option<u8>]>>(&'a self, data: B) -> Result<BufferSearchDynamicIter<'a>, Error> {
-        BufferSearchDynamicIter::new(self.as_slice(), data)
-    }
-    /// Check if this buffer contains the following [`u8`](u8) [slice](slice) sequence.
-    fn contains<B: AsRef<[u8]>>(&self, data: B) -> bool {
-        let buf = data.as_ref();
-
-        if buf.len() > self.len() { return false; }
-
-        let mut offset = 0usize;
-
-        for i in 0..self.len() {
-            if offset >= buf.len() { break; }
-
-            if *self.get(i).unwrap() != buf[offset] { offset = 0; continue; }
-            else { offset += 1; }
-        }
-
-        offset == buf.len()
-    }
-    /// Check if this buffer contains the following object of type *T*.
-    fn contains_ref<T: Castable>(&self, data: &T) -> Result<bool, Error> {
-        let bytes = ref_to_bytes::<T>(data)?;
-        Ok(self.contains(bytes))
-    }
-    /// Check if this buffer contains the following slice of type *T*.
-    fn contains_slice_ref<T: Castable>(&self, data: &[T]) -> Result<bool, Error> {
-        let bytes = slice_ref_to_bytes::<T>(data)?;
-        Ok(self.contains(bytes))
-    }
-    /// Check if this buffer starts with the byte sequence *needle*. See [`slice::starts_with`](slice::starts_with).
-    fn starts_with<B: AsRef<[u8]>>(&self, needle: B) -> bool {
-        self.as_slice().starts_with(needle.as_ref())
-    }
-    /// Check if this buffer ends with the byte sequence *needle*. See [`slice::ends_with`](slice::ends_with).
-    fn ends_with<B: AsRef<[u8]>>(&self, needle: B) -> bool {
-        self.as_slice().ends_with(needle.as_ref())
+        let op = typed_operation::<T>("end_with_slice_ref", None, std::mem::size_of_val(data));
+        let bytes = slice_ref_to_bytes::<T>(data).map_err(op)?;
+        self.end_with(bytes).map_err(op)
     }
     /// Rotate the buffer left at midpoint *mid*. See [`slice::rotate_left`](slice::rotate_left).
     fn rotate_left(&mut self, mid: usize) {
@@ -632,14 +1155,6 @@ pub trait Buffer {
     fn swap_with_data<B: AsMut<[u8]>>(&mut self, mut other: B) {
         self.as_mut_slice().swap_with_slice(other.as_mut());
     }
-    /// Check if this buffer is ASCII. See [`slice::is_ascii`](slice::is_ascii).
-    fn is_ascii(&self) -> bool {
-        self.as_slice().is_ascii()
-    }
-    /// Check if this buffer is equal while ignoring case of letters. See [`slice::eq_ignore_ascii_case`](slice::eq_ignore_ascii_case).
-    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
-        self.as_slice().eq_ignore_ascii_case(other)
-    }
     /// Make this buffer ASCII uppercase. See [`slice::make_ascii_uppercase`](slice::make_ascii_uppercase).
     fn make_ascii_uppercase(&mut self) {
         self.as_mut_slice().make_ascii_uppercase();
@@ -667,10 +1182,30 @@ pub trait Buffer {
     {
         self.as_mut_slice().sort_by_key(f);
     }
-    /// Creates a new `Buffer` object by repeating the current buffer *n* times. See [`slice::repeat`](slice::repeat).
-    fn repeat(&self, n: usize) -> Vec<u8> {
-        self.as_slice().repeat(n)
-    }
+}
+
+/// Convert the given *range* into a concrete [`Range`](std::ops::Range) within a buffer of size *len*.
+///
+/// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range does not fit within the buffer, and an
+/// [`Error::Overflow`](Error::Overflow) error if an inclusive end or exclusive start is `usize::MAX`.
+pub(crate) fn resolve_range<R: std::ops::RangeBounds<usize>>(len: usize, range: R) -> Result<std::ops::Range<usize>, Error> {
+    use std::ops::Bound;
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).ok_or(Error::Overflow(start))?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).ok_or(Error::Overflow(end))?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if end > len { return Err(Error::OutOfBounds(len,end)); }
+    if start > end { return Err(Error::OutOfBounds(end,start)); }
+
+    Ok(start..end)
 }
 
 /// An iterator for a [`Buffer`](Buffer) object.
@@ -740,7 +1275,7 @@ impl BufferSearchIter {
         
         let offsets: Vec<usize> = memmem::find_iter(buffer, search).collect();
 
-        Ok(Self { offsets: offsets, offset_index: 0 })
+        Ok(Self { offsets, offset_index: 0 })
     }
 }
 impl Iterator for BufferSearchIter {
@@ -766,21 +1301,17 @@ pub struct BufferSearchDynamicIter<'a> {
 }
 impl<'a> BufferSearchDynamicIter<'a> {
     fn find_next_const(&self, index: Option<usize>) -> Option<usize> {
-        let start;
-        
-        if index.is_none() {
-            start = 0;
-        }
-        else {
-            start = index.unwrap()+1;
-        }
+        let start = match index {
+            None => 0,
+            Some(i) => i+1,
+        };
 
         for i in start..self.term.len() {
             if self.term[i].is_none() { continue; }
             return Some(i);
         }
 
-        return None
+        None
     }
     
     /// Create a new search iterator over a buffer reference. Typically you'll just want to call [`Buffer::search_dynamic`](Buffer::search_dynamic) instead,
@@ -793,7 +1324,7 @@ impl<'a> BufferSearchDynamicIter<'a> {
         if search.len() > buffer.len() { return Err(Error::OutOfBounds(buffer.len(),search.len())); }
         
         let mut offsets = Vec::<usize>::new();
-        let mut result = Self { buffer: buffer, term: search.to_vec(), term_index: 0, offsets: offsets.clone(), offset_index: 0 };
+        let mut result = Self { buffer, term: search.to_vec(), term_index: 0, offsets: offsets.clone(), offset_index: 0 };
         let search_const = result.find_next_const(None);
 
         if search_const.is_none() { return Err(Error::SearchMatchesEverything); }
@@ -0,0 +1,432 @@
+use crate::{Buffer, BufferMut, Castable, Error, VecBuffer};
+use std::collections::{BTreeSet, HashMap};
+use std::marker::PhantomData;
+
+/// A typed handle to an object allocated by a [`BufferBuilder`](BufferBuilder).
+///
+/// A handle is just the offset of the object within the built buffer, so it remains valid
+/// as the builder grows and can be written into other objects as a file offset.
+pub struct Handle<T: Castable> {
+    offset: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+impl<T: Castable> Handle<T> {
+    /// Create a handle to an object of type *T* at the given *offset*.
+    pub fn new(offset: usize) -> Self {
+        Self { offset, _marker: PhantomData }
+    }
+    /// Get the offset of the object within the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+impl<T: Castable> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: Castable> Copy for Handle<T> {}
+impl<T: Castable> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+impl<T: Castable> Eq for Handle<T> {}
+impl<T: Castable> std::hash::Hash for Handle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.offset.hash(state);
+    }
+}
+impl<T: Castable> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Handle<{}>({:#x})", std::any::type_name::<T>(), self.offset)
+    }
+}
+
+/// A typed handle to an array of objects allocated by a [`BufferBuilder`](BufferBuilder).
+pub struct SliceHandle<T: Castable> {
+    offset: usize,
+    count: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+impl<T: Castable> SliceHandle<T> {
+    /// Create a handle to *count* objects of type *T* starting at the given *offset*.
+    pub fn new(offset: usize, count: usize) -> Self {
+        Self { offset, count, _marker: PhantomData }
+    }
+    /// Get the offset of the first object within the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Get the number of objects in the array.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+    /// Check whether or not the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// Get the size of the array in bytes.
+    pub fn size(&self) -> usize {
+        self.count * std::mem::size_of::<T>()
+    }
+    /// Get a handle to the object at the given *index*, or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Handle<T>> {
+        if index >= self.count { return None; }
+
+        Some(Handle::new(self.offset + index * std::mem::size_of::<T>()))
+    }
+}
+impl<T: Castable> Clone for SliceHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: Castable> Copy for SliceHandle<T> {}
+impl<T: Castable> PartialEq for SliceHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset && self.count == other.count
+    }
+}
+impl<T: Castable> Eq for SliceHandle<T> {}
+impl<T: Castable> std::hash::Hash for SliceHandle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.offset.hash(state);
+        self.count.hash(state);
+    }
+}
+impl<T: Castable> std::fmt::Debug for SliceHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SliceHandle<{}>({:#x}, {})", std::any::type_name::<T>(), self.offset, self.count)
+    }
+}
+
+/// Space reserved by [`BufferBuilder::reserve_patch`](BufferBuilder::reserve_patch) for a value which isn't known yet.
+///
+/// A patch can't be copied, and is consumed by [`BufferBuilder::patch`](BufferBuilder::patch), so each
+/// reservation is written exactly once.
+#[derive(Debug)]
+#[must_use = "reserved space must be filled in with BufferBuilder::patch"]
+pub struct Patch<T: Castable> {
+    offset: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+impl<T: Castable> Patch<T> {
+    /// Get the offset of the reserved space within the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// A position within a [`BufferBuilder`](BufferBuilder), which can be referenced before it's known.
+///
+/// See [`BufferBuilder::label`](BufferBuilder::label) and [`BufferBuilder::forward_label`](BufferBuilder::forward_label).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Label(usize);
+impl Label {
+    /// Get the id of this label, as reported by [`Error::UnresolvedLabel`](Error::UnresolvedLabel).
+    pub fn id(&self) -> usize {
+        self.0
+    }
+}
+
+/// How the value of a fixup is calculated from the offset of its [`Label`](Label).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum FixupKind {
+    /// The offset of the label within the buffer.
+    Absolute,
+    /// The distance from the start of the fixup field to the label, which is negative if the label
+    /// comes first. Negative values are written in two's complement.
+    Relative,
+    /// The distance from the given base offset to the label, such as an RVA relative to the start of a section.
+    BaseRelative(usize),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Fixup {
+    at: usize,
+    label: Label,
+    kind: FixupKind,
+    size: usize,
+}
+impl Fixup {
+    /// Calculate the value of this fixup given the offset of its label, truncated to the size of the fixup.
+    fn value(&self, target: usize) -> Result<u64, Error> {
+        let bits = self.size * 8;
+
+        let value = match self.kind {
+            FixupKind::Absolute => target as i128,
+            FixupKind::Relative => target as i128 - self.at as i128,
+            FixupKind::BaseRelative(base) => target as i128 - base as i128,
+        };
+
+        // relative fixups are signed, the others must be non-negative
+        let fits = match self.kind {
+            FixupKind::Relative => value >= -(1i128 << (bits-1)) && value < (1i128 << (bits-1)),
+            _ => value >= 0 && value < (1i128 << bits),
+        };
+
+        if !fits { return Err(Error::Overflow(target)); }
+
+        Ok(value as u64 & (u64::MAX >> (64 - bits)))
+    }
+}
+
+/// An arena for laying out a new binary file, one object at a time.
+///
+/// Objects are appended to the end of the buffer, aligned to their natural alignment, and their
+/// offsets are handed back as typed [`Handle`](Handle) objects. Values which aren't known until later,
+/// such as the size of a table or the offset of something written after it, can be reserved with
+/// [`BufferBuilder::reserve_patch`](BufferBuilder::reserve_patch) and filled in once they are. Offsets of
+/// things which haven't been written yet can also be recorded as fixups against a [`Label`](Label), which
+/// are filled in by [`BufferBuilder::resolve`](BufferBuilder::resolve).
+///
+/// Alignment is relative to the start of the buffer, which is what file formats care about. Since the
+/// memory of the underlying [`VecBuffer`](VecBuffer) need not be aligned, objects are copied in and out
+/// rather than referenced.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferBuilder, Castable};
+///
+/// #[repr(C)]
+/// #[derive(Copy, Clone, Castable)]
+/// struct Entry {
+///     name: u32,
+///     value: u32,
+/// }
+///
+/// let mut builder = BufferBuilder::new();
+/// let count = builder.reserve_patch::<u16>().unwrap();
+/// let entries = builder.alloc_slice::<Entry>(2).unwrap();
+/// let first = builder.alloc_str("first");
+/// let second = builder.alloc_str("second");
+///
+/// builder.write_slice(entries, &[
+///     Entry { name: first.offset() as u32, value: 1 },
+///     Entry { name: second.offset() as u32, value: 2 },
+/// ]).unwrap();
+/// builder.patch(count, entries.len() as u16).unwrap();
+///
+/// let buffer = builder.finish().unwrap();
+/// assert_eq!(buffer.read_val::<u16>(0).unwrap(), 2);
+/// assert_eq!(buffer.read_val::<Entry>(entries.offset()).unwrap().name, 0x14);
+/// assert_eq!(buffer.read(0x14, 6).unwrap(), b"first\0");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BufferBuilder {
+    buffer: VecBuffer,
+    strings: HashMap<Vec<u8>, usize>,
+    patches: BTreeSet<usize>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<Fixup>,
+}
+impl BufferBuilder {
+    /// Create a new, empty `BufferBuilder` object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Get the current size of the buffer being built.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+    /// Check whether or not anything has been allocated yet.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+    /// Get the buffer built so far. Reserved patches which haven't been filled in yet are zero.
+    pub fn as_buffer(&self) -> &VecBuffer {
+        &self.buffer
+    }
+    /// Pad the buffer with zeroes until its size is a multiple of *alignment*, returning the new size.
+    ///
+    /// # Panics
+    /// Panics if *alignment* is zero.
+    pub fn align(&mut self, alignment: usize) -> usize {
+        assert!(alignment != 0, "alignment must be non-zero");
+
+        let aligned = self.len().next_multiple_of(alignment);
+        self.buffer.resize(aligned, 0);
+        aligned
+    }
+    /// Allocate *size* zeroed bytes aligned to *alignment*, returning their offset.
+    ///
+    /// Returns an [`Error::Overflow`](Error::Overflow) error if the end of the allocation doesn't fit in a `usize`.
+    ///
+    /// # Panics
+    /// Panics if *alignment* is zero.
+    pub fn alloc_bytes(&mut self, size: usize, alignment: usize) -> Result<usize, Error> {
+        let offset = self.align(alignment);
+        let end = offset.checked_add(size).ok_or(Error::Overflow(offset))?;
+
+        self.buffer.resize(end, 0);
+        Ok(offset)
+    }
+    /// Allocate a zeroed object of type *T* at its natural alignment.
+    pub fn alloc<T: Castable>(&mut self) -> Result<Handle<T>, Error> {
+        Ok(Handle::new(self.alloc_bytes(std::mem::size_of::<T>(), std::mem::align_of::<T>())?))
+    }
+    /// Allocate an object of type *T* at its natural alignment and initialize it with *value*.
+    pub fn alloc_val<T: Castable>(&mut self, value: T) -> Result<Handle<T>, Error> {
+        let handle = self.alloc::<T>()?;
+        self.write(handle, value)?;
+        Ok(handle)
+    }
+    /// Allocate a zeroed array of *count* objects of type *T* at their natural alignment.
+    ///
+    /// Returns an [`Error::Overflow`](Error::Overflow) error if the size of the array doesn't fit in a `usize`.
+    pub fn alloc_slice<T: Castable>(&mut self, count: usize) -> Result<SliceHandle<T>, Error> {
+        let size = std::mem::size_of::<T>().checked_mul(count).ok_or(Error::Overflow(count))?;
+
+        Ok(SliceHandle::new(self.alloc_bytes(size, std::mem::align_of::<T>())?, count))
+    }
+    /// Allocate a NUL-terminated string, returning a handle to the string without its terminator.
+    ///
+    /// Strings are deduplicated: allocating the same string twice returns the handle of the first allocation.
+    pub fn alloc_str<S: AsRef<[u8]>>(&mut self, string: S) -> SliceHandle<u8> {
+        let string = string.as_ref();
+
+        if let Some(offset) = self.strings.get(string) {
+            return SliceHandle::new(*offset, string.len());
+        }
+
+        let offset = self.len();
+        self.buffer.append(string);
+        self.buffer.push(0);
+        self.strings.insert(string.to_vec(), offset);
+
+        SliceHandle::new(offset, string.len())
+    }
+    /// Reserve zeroed space for an object of type *T* whose value isn't known yet, such as a size or an offset.
+    ///
+    /// Fill it in later with [`BufferBuilder::patch`](BufferBuilder::patch). [`BufferBuilder::finish`](BufferBuilder::finish)
+    /// fails if any reservation is left unpatched.
+    pub fn reserve_patch<T: Castable>(&mut self) -> Result<Patch<T>, Error> {
+        let offset = self.alloc::<T>()?.offset();
+        self.patches.insert(offset);
+
+        Ok(Patch { offset, _marker: PhantomData })
+    }
+    /// Fill in the space reserved by [`BufferBuilder::reserve_patch`](BufferBuilder::reserve_patch) with *value*.
+    pub fn patch<T: Castable>(&mut self, patch: Patch<T>, value: T) -> Result<(), Error> {
+        self.buffer.write_val::<T>(patch.offset, value)?;
+        self.patches.remove(&patch.offset);
+        Ok(())
+    }
+    /// Read a copy of the object at the given *handle*.
+    pub fn read<T: Castable>(&self, handle: Handle<T>) -> Result<T, Error> {
+        self.buffer.read_val::<T>(handle.offset)
+    }
+    /// Write *value* to the object at the given *handle*.
+    pub fn write<T: Castable>(&mut self, handle: Handle<T>, value: T) -> Result<(), Error> {
+        if std::mem::size_of::<T>() == 0 { return Ok(()); }
+
+        self.buffer.write_val::<T>(handle.offset, value)
+    }
+    /// Write *values* to the array at the given *handle*.
+    ///
+    /// Returns an [`Error::SizeMismatch`](Error::SizeMismatch) error if the number of values doesn't match
+    /// the length of the array.
+    pub fn write_slice<T: Castable>(&mut self, handle: SliceHandle<T>, values: &[T]) -> Result<(), Error> {
+        if values.len() != handle.len() { return Err(Error::SizeMismatch(handle.len(),values.len())); }
+
+        self.buffer.write_vals::<T>(handle.offset, values)
+    }
+    /// Create a label at the current end of the buffer.
+    pub fn label(&mut self) -> Label {
+        let label = self.forward_label();
+        self.bind(label);
+        label
+    }
+    /// Create a label whose offset isn't known yet, so that fixups can refer to it before it's
+    /// bound with [`BufferBuilder::bind`](BufferBuilder::bind).
+    pub fn forward_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len()-1)
+    }
+    /// Bind the given *label* to the current end of the buffer, returning its offset.
+    ///
+    /// Binding a label again moves it.
+    pub fn bind(&mut self, label: Label) -> usize {
+        let offset = self.len();
+
+        if label.0 >= self.labels.len() { self.labels.resize(label.0+1, None); }
+
+        self.labels[label.0] = Some(offset);
+        offset
+    }
+    /// Get the offset of the given *label*, or `None` if it hasn't been bound yet.
+    pub fn label_offset(&self, label: Label) -> Option<usize> {
+        self.labels.get(label.0).copied().flatten()
+    }
+    /// Record a 32-bit fixup at the offset *at*, which [`BufferBuilder::resolve`](BufferBuilder::resolve)
+    /// fills in with the offset of *label* as calculated by *kind*.
+    pub fn fixup_u32(&mut self, at: usize, label: Label, kind: FixupKind) {
+        self.fixups.push(Fixup { at, label, kind, size: 4 });
+    }
+    /// Record a 64-bit fixup at the offset *at*. See [`BufferBuilder::fixup_u32`](BufferBuilder::fixup_u32).
+    pub fn fixup_u64(&mut self, at: usize, label: Label, kind: FixupKind) {
+        self.fixups.push(Fixup { at, label, kind, size: 8 });
+    }
+    /// Write every pending fixup recorded by [`BufferBuilder::fixup_u32`](BufferBuilder::fixup_u32) and
+    /// [`BufferBuilder::fixup_u64`](BufferBuilder::fixup_u64).
+    ///
+    /// Returns an [`Error::UnresolvedLabel`](Error::UnresolvedLabel) error if a fixup refers to a label which was
+    /// never bound and an [`Error::Overflow`](Error::Overflow) error if a value doesn't fit in its fixup. On error,
+    /// no fixups are written and all of them remain pending.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, BufferBuilder, FixupKind};
+    ///
+    /// let mut builder = BufferBuilder::new();
+    /// let table = builder.forward_label();
+    /// let table_offset = builder.alloc::<u32>().unwrap();
+    /// let table_distance = builder.alloc::<u32>().unwrap();
+    /// builder.fixup_u32(table_offset.offset(), table, FixupKind::Absolute);
+    /// builder.fixup_u32(table_distance.offset(), table, FixupKind::Relative);
+    ///
+    /// builder.alloc_bytes(8, 1).unwrap();
+    /// builder.bind(table);
+    /// builder.alloc_val::<u32>(0xDEADBEEF).unwrap();
+    ///
+    /// let buffer = builder.finish().unwrap();
+    /// assert_eq!(buffer.read_vals::<u32>(0, 2).unwrap(), [0x10, 0xC]);
+    /// ```
+    pub fn resolve(&mut self) -> Result<(), Error> {
+        let mut patches = Vec::<(Fixup, u64)>::with_capacity(self.fixups.len());
+
+        for fixup in &self.fixups {
+            let target = match self.label_offset(fixup.label) {
+                Some(offset) => offset,
+                None => return Err(Error::UnresolvedLabel(fixup.label.0)),
+            };
+
+            let end = fixup.at.checked_add(fixup.size).ok_or(Error::Overflow(fixup.at))?;
+            if end > self.len() { return Err(Error::OutOfBounds(self.len(),end)); }
+
+            patches.push((*fixup, fixup.value(target)?));
+        }
+
+        for (fixup, value) in patches {
+            if fixup.size == 4 { self.buffer.write_ref::<u32>(fixup.at, &(value as u32))?; }
+            else { self.buffer.write_ref::<u64>(fixup.at, &value)?; }
+        }
+
+        self.fixups.clear();
+        Ok(())
+    }
+    /// Resolve any pending fixups with [`BufferBuilder::resolve`](BufferBuilder::resolve) and return the built buffer.
+    ///
+    /// Returns an [`Error::UnpatchedReservation`](Error::UnpatchedReservation) error with the offset of the
+    /// first reservation which was never filled in by [`BufferBuilder::patch`](BufferBuilder::patch).
+    pub fn finish(mut self) -> Result<VecBuffer, Error> {
+        self.resolve()?;
+
+        if let Some(offset) = self.patches.iter().next() {
+            return Err(Error::UnpatchedReservation(*offset));
+        }
+
+        Ok(self.buffer)
+    }
+}
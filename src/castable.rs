@@ -18,14 +18,48 @@ use core::arch::x86_64;
 /// * The type allows any bit pattern (e.g., no `bool` or `char`).
 /// * The type does not contain any padding bytes.
 /// * The type's members are also `Castable`.
-/// * The type is `#[repr(C)]`, `#[repr(transparent)]`, `#[repr(packed)]` or `#[repr(align)]`.
-/// * The type must not use generics.
+/// * The type is `#[repr(C)]`, `#[repr(transparent)]` or `#[repr(packed)]`, optionally combined with `packed(N)` or `align(N)`.
+/// * The type must not use generics, unless it is a `#[repr(transparent)]` wrapper around `Castable` types.
 ///
 /// If you've used the [bytemuck](https://crates.io/crate/bytemuck) library,
 /// these rules will probably seem familiar. You can automatically guarantee these
 /// traits of your data with [the Castable derive macro](pkbuffer_derive::Castable).
+///
+/// # Safety
+/// Implementing this trait by hand asserts that all of the above rules hold for the type.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not Castable",
+    label = "`{Self}` does not implement `Castable`",
+    note = "derive `Castable` for your own types, or use integers, floats and arrays of Castable types",
+)]
 pub unsafe trait Castable {}
 
+/// The layout of a single field within a [`CastableLayout`](CastableLayout) object.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct FieldLayout {
+    /// The name of the field. Fields of tuple structs are named by their index.
+    pub name: &'static str,
+    /// The offset of the field from the start of the object.
+    pub offset: usize,
+    /// The size of the field.
+    pub size: usize,
+    /// The name of the field's type, as given by [`std::any::type_name`](std::any::type_name).
+    pub type_name: &'static str,
+    /// Whether or not the field is padding, such as the fields inserted by
+    /// [`#[castable(auto_pad)]`](pkbuffer_derive::castable).
+    pub padding: bool,
+}
+
+/// Field layout metadata for [`Castable`](Castable) objects.
+///
+/// This is implemented automatically by [the Castable derive macro](pkbuffer_derive::Castable), and is
+/// used by [`Buffer::dump_struct`](crate::Buffer::dump_struct) to show which field sits where and by
+/// [`Buffer::get_ref_checked`](crate::Buffer::get_ref_checked) to verify padding.
+pub trait CastableLayout: Castable {
+    /// Get the layout of each field of this type, in declaration order.
+    fn field_layout() -> Vec<FieldLayout>;
+}
+
 unsafe impl Castable for () {}
 unsafe impl Castable for u8 {}
 unsafe impl Castable for i8 {}
@@ -0,0 +1,217 @@
+use crate::{Buffer, Error, resolve_range};
+
+const fn make_crc32_table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            if crc & 1 != 0 { crc = (crc >> 1) ^ poly; }
+            else { crc >>= 1; }
+
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = make_crc32_table(0xEDB88320);
+static CRC32C_TABLE: [u32; 256] = make_crc32_table(0x82F63B78);
+
+const ADLER32_MOD: u32 = 65521;
+// the largest number of bytes that can be summed before the adler32 accumulators overflow a u32
+const ADLER32_NMAX: usize = 5552;
+
+const FNV1A32_OFFSET: u32 = 0x811C9DC5;
+const FNV1A32_PRIME: u32 = 0x01000193;
+const FNV1A64_OFFSET: u64 = 0xCBF29CE484222325;
+const FNV1A64_PRIME: u64 = 0x00000100000001B3;
+
+/// The checksum algorithms supported by [`Buffer::checksum`](Buffer::checksum) and [`Checksum`](Checksum).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Algorithm {
+    /// The CRC-32 (IEEE 802.3) checksum, as used by zip, gzip and PNG.
+    Crc32,
+    /// The CRC-32C (Castagnoli) checksum, as used by iSCSI, ext4 and SSE4.2.
+    Crc32c,
+    /// The Adler-32 checksum, as used by zlib.
+    Adler32,
+    /// The 32-bit FNV-1a hash.
+    Fnv1a32,
+    /// The 64-bit FNV-1a hash.
+    Fnv1a64,
+    /// The 16-bit one's-complement checksum used by IP, TCP and UDP headers (see RFC 1071).
+    Internet,
+    /// The checksum of a PE image, which is a 16-bit one's-complement sum plus the size of the data.
+    ///
+    /// The argument is the offset of the 4-byte `CheckSum` field of the optional header,
+    /// relative to the start of the checksummed data. Its bytes are treated as zero.
+    Pe(usize),
+}
+
+/// A streaming checksum calculator.
+///
+/// This is useful for calculating a checksum over multiple non-contiguous pieces of data,
+/// such as the ranges of multiple [`Buffer`](Buffer) objects.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Algorithm, Buffer, Checksum, VecBuffer};
+///
+/// let buffer = VecBuffer::from_data(b"123456789");
+/// let mut checksum = Checksum::new(Algorithm::Crc32);
+/// assert!(checksum.update_buffer(&buffer, ..4).is_ok());
+/// assert!(checksum.update_buffer(&buffer, 4..).is_ok());
+/// assert_eq!(checksum.finish(), 0xCBF43926);
+/// assert_eq!(buffer.checksum(.., Algorithm::Crc32).unwrap(), 0xCBF43926);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Checksum {
+    algorithm: Algorithm,
+    state: u64,
+    length: usize,
+    pending: Option<u8>,
+}
+impl Checksum {
+    /// Create a new checksum calculator for the given *algorithm*.
+    pub fn new(algorithm: Algorithm) -> Self {
+        let state = match algorithm {
+            Algorithm::Crc32 | Algorithm::Crc32c => 0xFFFFFFFF,
+            Algorithm::Adler32 => 1,
+            Algorithm::Fnv1a32 => FNV1A32_OFFSET as u64,
+            Algorithm::Fnv1a64 => FNV1A64_OFFSET,
+            Algorithm::Internet | Algorithm::Pe(_) => 0,
+        };
+
+        Self { algorithm, state, length: 0, pending: None }
+    }
+    /// Get the algorithm used by this checksum calculator.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+    /// Get the number of bytes processed by this checksum calculator so far.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+    /// Check whether or not this checksum calculator has processed any bytes yet.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+    /// Reset this checksum calculator to its initial state.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.algorithm);
+    }
+    /// Add the given *data* to the checksum.
+    pub fn update<B: AsRef<[u8]>>(&mut self, data: B) {
+        let data = data.as_ref();
+
+        match self.algorithm {
+            Algorithm::Crc32 => self.update_crc32(&CRC32_TABLE, data),
+            Algorithm::Crc32c => self.update_crc32(&CRC32C_TABLE, data),
+            Algorithm::Adler32 => self.update_adler32(data),
+            Algorithm::Fnv1a32 => {
+                let mut hash = self.state as u32;
+
+                for byte in data {
+                    hash = (hash ^ *byte as u32).wrapping_mul(FNV1A32_PRIME);
+                }
+
+                self.state = hash as u64;
+            },
+            Algorithm::Fnv1a64 => {
+                for byte in data {
+                    self.state = (self.state ^ *byte as u64).wrapping_mul(FNV1A64_PRIME);
+                }
+            },
+            Algorithm::Internet => self.update_ones_complement(data, true, None),
+            Algorithm::Pe(checksum_offset) => self.update_ones_complement(data, false, Some(checksum_offset)),
+        }
+
+        self.length += data.len();
+    }
+    /// Add the given *range* of the given [`Buffer`](Buffer) to the checksum.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds of the buffer.
+    pub fn update_buffer<B: Buffer + ?Sized, R: std::ops::RangeBounds<usize>>(&mut self, buffer: &B, range: R) -> Result<(), Error> {
+        let range = resolve_range(buffer.len(), range)?;
+        self.update(&buffer.as_slice()[range]);
+        Ok(())
+    }
+    /// Get the checksum of the data processed so far.
+    ///
+    /// 16-bit and 32-bit checksums are zero-extended to a [`u64`](u64).
+    pub fn finish(&self) -> u64 {
+        match self.algorithm {
+            Algorithm::Crc32 | Algorithm::Crc32c => self.state ^ 0xFFFFFFFF,
+            Algorithm::Adler32 | Algorithm::Fnv1a32 | Algorithm::Fnv1a64 => self.state,
+            Algorithm::Internet => {
+                let sum = self.pending.map_or(self.state, |byte| self.state + ((byte as u64) << 8));
+                !fold_ones_complement(sum) & 0xFFFF
+            },
+            Algorithm::Pe(_) => {
+                let sum = self.pending.map_or(self.state, |byte| self.state + byte as u64);
+                (fold_ones_complement(sum) + self.length as u64) & 0xFFFFFFFF
+            },
+        }
+    }
+
+    fn update_crc32(&mut self, table: &[u32; 256], data: &[u8]) {
+        let mut crc = self.state as u32;
+
+        for byte in data {
+            crc = table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+
+        self.state = crc as u64;
+    }
+    fn update_adler32(&mut self, data: &[u8]) {
+        let mut a = (self.state & 0xFFFF) as u32;
+        let mut b = (self.state >> 16) as u32;
+
+        for chunk in data.chunks(ADLER32_NMAX) {
+            for byte in chunk {
+                a += *byte as u32;
+                b += a;
+            }
+
+            a %= ADLER32_MOD;
+            b %= ADLER32_MOD;
+        }
+
+        self.state = ((b as u64) << 16) | a as u64;
+    }
+    fn update_ones_complement(&mut self, data: &[u8], big_endian: bool, skip: Option<usize>) {
+        for (index, byte) in data.iter().enumerate() {
+            let position = self.length + index;
+            let byte = match skip {
+                Some(offset) if position >= offset && position < offset+4 => 0,
+                _ => *byte,
+            };
+
+            match self.pending.take() {
+                None => self.pending = Some(byte),
+                Some(first) => {
+                    let word = if big_endian { u16::from_be_bytes([first, byte]) }
+                               else { u16::from_le_bytes([first, byte]) };
+
+                    self.state = fold_ones_complement(self.state + word as u64);
+                },
+            }
+        }
+    }
+}
+
+fn fold_ones_complement(mut sum: u64) -> u64 {
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    sum
+}
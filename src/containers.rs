@@ -0,0 +1,132 @@
+//! [`Buffer`](Buffer) and [`BufferMut`](BufferMut) support for standard containers of bytes.
+
+use crate::{Buffer, BufferMut};
+
+/// An adapter which makes any container of bytes a [`Buffer`](Buffer), such as `Vec<u8>`, `Box<[u8]>`,
+/// `[u8; N]`, `&mut [u8]`, `Cow<[u8]>`, `Arc<[u8]>` or, with the `bytes` feature, `bytes::Bytes` and `bytes::BytesMut`.
+///
+/// These containers dereference to `[u8]`, whose methods share names with `Buffer` methods such as `contains`,
+/// `iter`, `get` and `fill`. Implementing `Buffer` on the containers themselves would make the trait methods
+/// shadow the slice methods wherever `pkbuffer::*` is imported, so they're wrapped instead. Any `AsRef<[u8]>`
+/// type is a `Buffer` once wrapped, and any `AsRef<[u8]> + AsMut<[u8]>` type is also a [`BufferMut`](BufferMut).
+/// To write to a `Cow`, wrap the `&mut Vec<u8>` returned by [`Cow::to_mut`](std::borrow::Cow::to_mut).
+///
+/// `&[u8]` can't shadow slice methods, so it implements `Buffer` directly.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferMut, SliceBuffer};
+///
+/// let mut data = SliceBuffer::new(vec![0u8; 8]);
+/// data.write_val::<u32>(4, 0xDEADBEEF).unwrap();
+/// assert_eq!(*data.get_ref::<u32>(4).unwrap(), 0xDEADBEEF);
+///
+/// let data = data.into_inner();
+/// assert!(data.contains(&0xEF));
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SliceBuffer<T>(pub T);
+impl<T> SliceBuffer<T> {
+    /// Wrap the given container of bytes.
+    pub fn new(data: T) -> Self {
+        Self(data)
+    }
+    /// Get a reference to the wrapped container.
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+    /// Get a mutable reference to the wrapped container.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+    /// Unwrap the container.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+impl<T> From<T> for SliceBuffer<T> {
+    fn from(data: T) -> Self {
+        Self(data)
+    }
+}
+impl<T: AsRef<[u8]>> Buffer for SliceBuffer<T> {
+    fn len(&self) -> usize {
+        self.0.as_ref().len()
+    }
+    fn as_ptr(&self) -> *const u8 {
+        self.0.as_ref().as_ptr()
+    }
+    fn as_slice(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+impl<T: AsRef<[u8]> + AsMut<[u8]>> BufferMut for SliceBuffer<T> {
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.0.as_mut().as_mut_ptr()
+    }
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.0.as_mut()
+    }
+}
+impl<T: AsRef<[u8]>> PartialEq<[u8]> for SliceBuffer<T> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl<T: AsRef<[u8]>, const N: usize> PartialEq<[u8; N]> for SliceBuffer<T> {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl<T: AsRef<[u8]>> PartialEq<Vec<u8>> for SliceBuffer<T> {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: AsRef<[u8]>, U: Buffer> PartialEq<U> for SliceBuffer<T> {
+    fn eq(&self, other: &U) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: AsRef<[u8]>> Eq for SliceBuffer<T> {}
+impl<T: AsRef<[u8]>, Idx: std::slice::SliceIndex<[u8]>> std::ops::Index<Idx> for SliceBuffer<T> {
+    type Output = Idx::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+impl<T: AsRef<[u8]> + AsMut<[u8]>, Idx: std::slice::SliceIndex<[u8]>> std::ops::IndexMut<Idx> for SliceBuffer<T> {
+    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+        self.as_mut_slice().index_mut(index)
+    }
+}
+impl<T: AsRef<[u8]>> std::convert::AsRef<[u8]> for SliceBuffer<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+impl<T: AsRef<[u8]> + AsMut<[u8]>> std::convert::AsMut<[u8]> for SliceBuffer<T> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+impl<T: AsRef<[u8]>> std::hash::Hash for SliceBuffer<T> {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: std::hash::Hasher
+    {
+        self.as_slice().hash(state);
+    }
+}
+impl Buffer for &[u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+    fn as_ptr(&self) -> *const u8 {
+        <[u8]>::as_ptr(self)
+    }
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+}
@@ -0,0 +1,98 @@
+/// Get the contribution of a byte count to the entropy sum, `count * log2(count)`.
+fn count_weight(count: usize) -> f64 {
+    if count == 0 { 0.0 }
+    else { count as f64 * (count as f64).log2() }
+}
+
+/// Calculate the Shannon entropy of a given byte *histogram*, in bits per byte.
+///
+/// The result ranges from `0.0` (every byte is the same) to `8.0` (every byte value is
+/// equally likely). An empty histogram has an entropy of `0.0`.
+pub fn histogram_entropy(histogram: &[usize; 256]) -> f64 {
+    let total: usize = histogram.iter().sum();
+    let weights: f64 = histogram.iter().map(|count| count_weight(*count)).sum();
+
+    entropy_from_weights(total, weights)
+}
+
+fn entropy_from_weights(total: usize, weights: f64) -> f64 {
+    if total == 0 { return 0.0; }
+
+    let total = total as f64;
+    (total.log2() - weights / total).max(0.0)
+}
+
+/// An iterator over the entropy of sliding windows of a [`Buffer`](crate::Buffer).
+///
+/// Each item is a pair of the window's offset and its entropy in bits per byte. The byte
+/// histogram is updated incrementally as the window slides, so each step only costs as much
+/// as the bytes entering and leaving the window. Only full windows are yielded, similar
+/// to [`slice::windows`](slice::windows).
+///
+/// See [`Buffer::windows_entropy`](crate::Buffer::windows_entropy).
+pub struct EntropyWindows<'a> {
+    buffer: &'a [u8],
+    window: usize,
+    step: usize,
+    offset: usize,
+    histogram: [usize; 256],
+    weights: f64,
+    primed: bool,
+}
+impl<'a> EntropyWindows<'a> {
+    /// Create a new sliding window entropy iterator over the given *buffer* with the given *window*
+    /// size, advancing *step* bytes each iteration. Typically you'll just want to call
+    /// [`Buffer::windows_entropy`](crate::Buffer::windows_entropy) instead.
+    ///
+    /// # Panics
+    /// Panics if *window* or *step* is zero.
+    pub fn new(buffer: &'a [u8], window: usize, step: usize) -> Self {
+        assert!(window != 0, "window size must be non-zero");
+        assert!(step != 0, "step size must be non-zero");
+
+        Self { buffer, window, step, offset: 0, histogram: [0usize; 256], weights: 0.0, primed: false }
+    }
+
+    fn add(&mut self, byte: u8) {
+        let count = &mut self.histogram[byte as usize];
+        self.weights += count_weight(*count+1) - count_weight(*count);
+        *count += 1;
+    }
+    fn remove(&mut self, byte: u8) {
+        let count = &mut self.histogram[byte as usize];
+        self.weights += count_weight(*count-1) - count_weight(*count);
+        *count -= 1;
+    }
+    fn rebuild(&mut self, offset: usize) {
+        self.histogram = [0usize; 256];
+        self.weights = 0.0;
+
+        for i in offset..offset+self.window {
+            self.add(self.buffer[i]);
+        }
+    }
+}
+impl<'a> Iterator for EntropyWindows<'a> {
+    type Item = (usize, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = if self.primed { self.offset + self.step } else { 0 };
+
+        if self.window > self.buffer.len() || offset > self.buffer.len() - self.window { return None; }
+
+        if !self.primed || self.step >= self.window {
+            self.rebuild(offset);
+        }
+        else {
+            for i in self.offset..offset {
+                self.remove(self.buffer[i]);
+                self.add(self.buffer[i+self.window]);
+            }
+        }
+
+        self.primed = true;
+        self.offset = offset;
+
+        Some((offset, entropy_from_weights(self.window, self.weights)))
+    }
+}
@@ -0,0 +1,159 @@
+//! Interoperability with the `bytes`, `bytemuck` and `zerocopy` crates, each behind a feature of the same name.
+//!
+//! `bytes::Bytes` and `bytes::BytesMut` dereference to `[u8]` like the standard containers, so they're used as
+//! buffers through [`SliceBuffer`](crate::SliceBuffer) rather than implementing [`Buffer`](crate::Buffer) themselves.
+
+#[cfg(any(feature = "bytemuck", feature = "zerocopy"))]
+use crate::Castable;
+#[cfg(feature = "bytes")]
+use crate::{Buffer, VecBuffer};
+
+#[cfg(feature = "bytes")]
+impl From<bytes::BytesMut> for VecBuffer {
+    fn from(data: bytes::BytesMut) -> Self {
+        VecBuffer::from_data(data)
+    }
+}
+#[cfg(feature = "bytes")]
+impl From<VecBuffer> for bytes::BytesMut {
+    fn from(buffer: VecBuffer) -> Self {
+        bytes::BytesMut::from(buffer.as_slice())
+    }
+}
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for VecBuffer {
+    fn from(data: bytes::Bytes) -> Self {
+        VecBuffer::from_data(data)
+    }
+}
+#[cfg(feature = "bytes")]
+impl From<VecBuffer> for bytes::Bytes {
+    fn from(buffer: VecBuffer) -> Self {
+        bytes::Bytes::from(buffer.to_vec())
+    }
+}
+
+/// A wrapper which makes any [`bytemuck::Pod`](bytemuck::Pod) type [`Castable`](Castable).
+///
+/// Both traits make the same promises about a type, but neither direction can be bridged with a blanket
+/// implementation:
+///
+/// * `impl<T: bytemuck::Pod> Castable for T` is rejected by coherence (E0119), since `()`, the integers,
+///   the floats, arrays and `Wrapping` already implement `Castable` and are all `Pod` too. It would also
+///   overlap the equivalent `zerocopy` impl when both features are enabled, and stop downstream crates from
+///   deriving `Castable` on their own `Pod` types, so turning on a feature would break builds.
+/// * `impl<T: Castable> bytemuck::Pod for T` is rejected by the orphan rule (E0210), since both the trait
+///   and `T` are foreign to `bytemuck`.
+///
+/// Since the wrapper is `#[repr(transparent)]`, it can be used anywhere a `Castable` type can, and
+/// dereferences to the wrapped type.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferMut, BytemuckCastable, VecBuffer};
+///
+/// #[repr(C)]
+/// #[derive(Copy, Clone)]
+/// struct Vertex {
+///     x: f32,
+///     y: f32,
+/// }
+/// unsafe impl bytemuck::Zeroable for Vertex {}
+/// unsafe impl bytemuck::Pod for Vertex {}
+///
+/// let mut buffer = VecBuffer::with_initial_size(8);
+/// let vertex = Vertex { x: 1.0, y: 2.0 };
+/// assert!(buffer.write_ref(0, BytemuckCastable::from_ref(&vertex)).is_ok());
+///
+/// let vertex = buffer.get_ref::<BytemuckCastable<Vertex>>(0).unwrap();
+/// assert_eq!(vertex.y, 2.0);
+/// ```
+#[cfg(feature = "bytemuck")]
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BytemuckCastable<T: bytemuck::Pod>(pub T);
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod> BytemuckCastable<T> {
+    /// Wrap a reference to a `Pod` object.
+    pub fn from_ref(value: &T) -> &Self {
+        // SAFETY: Self is a transparent wrapper around T
+        unsafe { &*(value as *const T as *const Self) }
+    }
+    /// Wrap a mutable reference to a `Pod` object.
+    pub fn from_mut(value: &mut T) -> &mut Self {
+        // SAFETY: Self is a transparent wrapper around T
+        unsafe { &mut *(value as *mut T as *mut Self) }
+    }
+    /// Unwrap the `Pod` object.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::Pod> Castable for BytemuckCastable<T> {}
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod> std::ops::Deref for BytemuckCastable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+#[cfg(feature = "bytemuck")]
+impl<T: bytemuck::Pod> std::ops::DerefMut for BytemuckCastable<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A wrapper which makes any [`zerocopy::FromBytes`](zerocopy::FromBytes) + [`zerocopy::AsBytes`](zerocopy::AsBytes)
+/// type [`Castable`](Castable).
+///
+/// A blanket `impl<T: FromBytes + AsBytes> Castable for T` overlaps the primitive `Castable` impls for the
+/// same reasons as `bytemuck`'s, see [`BytemuckCastable`](BytemuckCastable), so this is a transparent wrapper too.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, VecBuffer, ZerocopyCastable};
+///
+/// let buffer = VecBuffer::from_data([0xEF, 0xBE, 0xAD, 0xDE]);
+/// let words = buffer.get_ref::<ZerocopyCastable<[u16; 2]>>(0).unwrap();
+/// assert_eq!(**words, [0xBEEF, 0xDEAD]);
+/// ```
+#[cfg(feature = "zerocopy")]
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ZerocopyCastable<T: zerocopy::FromBytes + zerocopy::AsBytes>(pub T);
+#[cfg(feature = "zerocopy")]
+impl<T: zerocopy::FromBytes + zerocopy::AsBytes> ZerocopyCastable<T> {
+    /// Wrap a reference to a zerocopy object.
+    pub fn from_ref(value: &T) -> &Self {
+        // SAFETY: Self is a transparent wrapper around T
+        unsafe { &*(value as *const T as *const Self) }
+    }
+    /// Wrap a mutable reference to a zerocopy object.
+    pub fn from_mut(value: &mut T) -> &mut Self {
+        // SAFETY: Self is a transparent wrapper around T
+        unsafe { &mut *(value as *mut T as *mut Self) }
+    }
+    /// Unwrap the zerocopy object.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+#[cfg(feature = "zerocopy")]
+unsafe impl<T: zerocopy::FromBytes + zerocopy::AsBytes> Castable for ZerocopyCastable<T> {}
+#[cfg(feature = "zerocopy")]
+impl<T: zerocopy::FromBytes + zerocopy::AsBytes> std::ops::Deref for ZerocopyCastable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+#[cfg(feature = "zerocopy")]
+impl<T: zerocopy::FromBytes + zerocopy::AsBytes> std::ops::DerefMut for ZerocopyCastable<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
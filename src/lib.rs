@@ -5,7 +5,7 @@
 //!
 //! For example:
 //! ```rust
-//! use pkbuffer::{Buffer, VecBuffer, Castable};
+//! use pkbuffer::{Buffer, BufferMut, VecBuffer, Castable};
 //!
 //! #[repr(packed)]
 //! #[derive(Copy, Clone, Castable)]
@@ -30,10 +30,11 @@
 //!
 //! Buffer objects are derived from the [`Buffer`](Buffer) trait. This trait
 //! implements much functionality of slice objects as well as data casting
-//! abilities of the derived Buffer objects.
+//! abilities of the derived Buffer objects. Buffers which can be written to additionally
+//! implement the [`BufferMut`](BufferMut) trait, which holds the writing half of that functionality.
 //!
-//! Buffer objects comes in two forms: *pointer form* ([`PtrBuffer`](PtrBuffer)) and
-//! *allocated form* ([`VecBuffer`](VecBuffer)). Each of these structures come
+//! Buffer objects comes in two forms: *pointer form* ([`PtrBuffer`](PtrBuffer) and
+//! [`PtrBufferMut`](PtrBufferMut)) and *allocated form* ([`VecBuffer`](VecBuffer)). Each of these structures come
 //! in handy for different reasons. [`PtrBuffer`](PtrBuffer) is useful on unowned data
 //! such as arbitrary locations in memory, whereas [`VecBuffer`](VecBuffer)'s
 //! utility comes from being able to manipulate the underlying owned data.
@@ -56,18 +57,81 @@ mod tests;
 mod buffer;
 pub use buffer::*;
 
+mod builder;
+pub use builder::*;
+
 mod castable;
 pub use castable::*;
 
+mod checksum;
+pub use checksum::*;
+
+mod containers;
+pub use containers::*;
+
+mod entropy;
+pub use entropy::*;
+
+#[cfg(any(feature = "bytes", feature = "bytemuck", feature = "zerocopy"))]
+mod interop;
+#[cfg(any(feature = "bytemuck", feature = "zerocopy"))]
+pub use interop::*;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rayon")]
+pub(crate) use parallel::*;
+
+mod parse;
+pub use parse::*;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod process;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use process::*;
+
 mod ptr;
 pub use ptr::*;
 
+#[cfg(feature = "serde")]
+pub mod serialize;
+
+mod shared;
+pub use shared::*;
+
+mod split;
+pub use split::*;
+
+mod stream;
+pub use stream::*;
+
+mod transaction;
+pub use transaction::*;
+
 mod vec;
 pub use vec::*;
 
+mod view;
+pub use view::*;
+
 pub use pkbuffer_derive::*;
 
 /// Errors produced by the library.
+///
+/// Errors returned by [`Buffer`](Buffer) and [`BufferMut`](BufferMut) operations are wrapped in an
+/// [`Error::Context`](Error::Context) error describing the failed operation, and callers can add context of their
+/// own with [`Error::context`](Error::context) or [`ResultExt::context`](ResultExt::context). Use
+/// [`Error::root`](Error::root) to get at the underlying error:
+///
+/// ```rust
+/// use pkbuffer::{Buffer, Error, ResultExt, VecBuffer};
+///
+/// let buffer = VecBuffer::from_data([0u8; 8]);
+/// let error = buffer.read_val::<u32>(6).context("parsing section table").unwrap_err();
+///
+/// assert_eq!(error.root(), &Error::OutOfBounds(8, 10));
+/// assert_eq!(error.to_string(), "parsing section table: read_val::<u32> at 0x6 (0x4 bytes): out of bounds: boundary is 0x8, got 0xa instead");
+/// ```
 #[derive(Debug)]
 pub enum Error {
     /// An error produced by [`std::io::Error`](std::io::Error).
@@ -77,9 +141,9 @@ pub enum Error {
     /// The first arg represents the current boundary, the second arg
     /// represents the out-of-bounds argument.
     OutOfBounds(usize,usize),
-    /// The operation produced an invalid pointer. Argument is the pointer
-    /// in question.
-    InvalidPointer(*const u8),
+    /// The operation produced an invalid pointer. Argument is the address
+    /// of the pointer in question.
+    InvalidPointer(usize),
     /// The alignment of the given operation is off. The first arg
     /// represents the expected alignment, the second argument represents
     /// the alignment of the given object relative to the expected alignment.
@@ -91,23 +155,108 @@ pub enum Error {
     SizeMismatch(usize,usize),
     /// The search term would match everything.
     SearchMatchesEverything,
+    /// The value could not be represented in the target type. Argument is the
+    /// value in question.
+    Overflow(usize),
+    /// A padding byte of an object was not zero. Argument is the offset
+    /// of the byte in question.
+    NonZeroPadding(usize),
+    /// Space reserved for a value was never filled in. Argument is the offset
+    /// of the reservation in question.
+    UnpatchedReservation(usize),
+    /// A fixup referred to a label which was never bound. Argument is the
+    /// id of the label in question.
+    UnresolvedLabel(usize),
+    /// A region of a [`SharedBuffer`](SharedBuffer) overlaps a region which is already borrowed.
+    /// The first arg is the start of the requested region, the second arg is its end.
+    RegionBorrowed(usize,usize),
+    /// Ranges which were meant to be disjoint overlap. The first arg is the start of the
+    /// offending range, the second arg is its end.
+    OverlappingRanges(usize,usize),
+    /// An error with context attached. The first arg is the underlying error,
+    /// the second arg describes what was being done when it occurred.
+    Context(Box<Error>,ErrorContext),
+}
+impl Error {
+    /// Get the [`ErrorKind`](ErrorKind) of this error, for matching errors without destructuring them.
+    ///
+    /// The kind of an [`Error::Context`](Error::Context) error is the kind of its underlying error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, ErrorKind, VecBuffer};
+    ///
+    /// let buffer = VecBuffer::from_data([0u8; 4]);
+    /// assert_eq!(buffer.get_ref::<u64>(0).unwrap_err().kind(), ErrorKind::OutOfBounds);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self.root() {
+            Self::IoError(_) => ErrorKind::IoError,
+            Self::OutOfBounds(_,_) => ErrorKind::OutOfBounds,
+            Self::InvalidPointer(_) => ErrorKind::InvalidPointer,
+            Self::BadAlignment(_,_) => ErrorKind::BadAlignment,
+            Self::ZeroSizedType => ErrorKind::ZeroSizedType,
+            Self::SizeMismatch(_,_) => ErrorKind::SizeMismatch,
+            Self::SearchMatchesEverything => ErrorKind::SearchMatchesEverything,
+            Self::Overflow(_) => ErrorKind::Overflow,
+            Self::NonZeroPadding(_) => ErrorKind::NonZeroPadding,
+            Self::UnpatchedReservation(_) => ErrorKind::UnpatchedReservation,
+            Self::UnresolvedLabel(_) => ErrorKind::UnresolvedLabel,
+            Self::RegionBorrowed(_,_) => ErrorKind::RegionBorrowed,
+            Self::OverlappingRanges(_,_) => ErrorKind::OverlappingRanges,
+            Self::Context(_,_) => unreachable!("the root of an error is never a context error"),
+        }
+    }
+    /// Get the underlying error, skipping past any [`Error::Context`](Error::Context) wrappers.
+    pub fn root(&self) -> &Error {
+        let mut error = self;
+
+        while let Self::Context(inner, _) = error {
+            error = inner;
+        }
+
+        error
+    }
+    /// Wrap this error with the given context message.
+    pub fn context<C: Into<String>>(self, context: C) -> Self {
+        Self::Context(Box::new(self), ErrorContext::Message(context.into()))
+    }
+    /// Attach the failed operation to this error. Since operations are frequently built on top of
+    /// one another, the operation of a nested call is replaced, so the error names the operation
+    /// that was actually called.
+    pub(crate) fn in_operation(self, name: &'static str, type_name: Option<&'static str>, offset: Option<usize>, length: usize) -> Self {
+        let operation = ErrorContext::Operation { name, type_name, offset, length };
+
+        match self {
+            Self::Context(inner, ErrorContext::Operation { .. }) => Self::Context(inner, operation),
+            error => Self::Context(Box::new(error), operation),
+        }
+    }
 }
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::IoError(io) => write!(f, "i/o error: {}", io.to_string()),
+            Self::IoError(io) => write!(f, "i/o error: {}", io),
             Self::OutOfBounds(expected,got) => write!(f, "out of bounds: boundary is {:#x}, got {:#x} instead", expected, got),
-            Self::InvalidPointer(ptr) => write!(f, "invalid pointer: {:p}", ptr),
+            Self::InvalidPointer(address) => write!(f, "invalid pointer: {:#x}", address),
             Self::BadAlignment(expected,got) => write!(f, "bad alignment: expected {}-byte alignment, but alignment is off by {}", expected, got),
             Self::ZeroSizedType => write!(f, "zero sized type"),
             Self::SizeMismatch(expected,got) => write!(f, "size mismatch: the two types differed in size, expected {}, got {}", expected, got),
             Self::SearchMatchesEverything => write!(f, "the search would match everything in the binary"),
+            Self::Overflow(value) => write!(f, "overflow: {:#x} cannot be represented by the target type", value),
+            Self::NonZeroPadding(offset) => write!(f, "non-zero padding: the padding byte at {:#x} is not zero", offset),
+            Self::UnpatchedReservation(offset) => write!(f, "unpatched reservation: the space reserved at {:#x} was never filled in", offset),
+            Self::UnresolvedLabel(id) => write!(f, "unresolved label: label {} was never bound", id),
+            Self::RegionBorrowed(start,end) => write!(f, "region borrowed: {:#x}..{:#x} overlaps a region which is already borrowed", start, end),
+            Self::OverlappingRanges(start,end) => write!(f, "overlapping ranges: {:#x}..{:#x} overlaps another range", start, end),
+            Self::Context(error,context) => write!(f, "{}: {}", context, error),
         }
     }
 }
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
+        // the display of a context error already includes the wrapped error, so skip straight to its source
+        match self.root() {
             Self::IoError(ref e) => Some(e),
             _ => None,
         }
@@ -118,8 +267,105 @@ impl std::convert::From<std::io::Error> for Error {
         Self::IoError(io_err)
     }
 }
-unsafe impl Send for Error {}
-unsafe impl Sync for Error {}
+/// [`std::io::Error`](std::io::Error) objects can't be compared, so [`Error::IoError`](Error::IoError)
+/// errors are equal when their [`kind`](std::io::Error::kind)s are.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::IoError(a), Self::IoError(b)) => a.kind() == b.kind(),
+            (Self::OutOfBounds(a1,a2), Self::OutOfBounds(b1,b2)) => a1 == b1 && a2 == b2,
+            (Self::InvalidPointer(a), Self::InvalidPointer(b)) => a == b,
+            (Self::BadAlignment(a1,a2), Self::BadAlignment(b1,b2)) => a1 == b1 && a2 == b2,
+            (Self::ZeroSizedType, Self::ZeroSizedType) => true,
+            (Self::SizeMismatch(a1,a2), Self::SizeMismatch(b1,b2)) => a1 == b1 && a2 == b2,
+            (Self::SearchMatchesEverything, Self::SearchMatchesEverything) => true,
+            (Self::Overflow(a), Self::Overflow(b)) => a == b,
+            (Self::NonZeroPadding(a), Self::NonZeroPadding(b)) => a == b,
+            (Self::UnpatchedReservation(a), Self::UnpatchedReservation(b)) => a == b,
+            (Self::UnresolvedLabel(a), Self::UnresolvedLabel(b)) => a == b,
+            (Self::RegionBorrowed(a1,a2), Self::RegionBorrowed(b1,b2)) => a1 == b1 && a2 == b2,
+            (Self::OverlappingRanges(a1,a2), Self::OverlappingRanges(b1,b2)) => a1 == b1 && a2 == b2,
+            (Self::Context(a1,a2), Self::Context(b1,b2)) => a1 == b1 && a2 == b2,
+            _ => false,
+        }
+    }
+}
+
+/// The kind of an [`Error`](Error), without its arguments. See [`Error::kind`](Error::kind).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum ErrorKind {
+    /// See [`Error::IoError`](Error::IoError).
+    IoError,
+    /// See [`Error::OutOfBounds`](Error::OutOfBounds).
+    OutOfBounds,
+    /// See [`Error::InvalidPointer`](Error::InvalidPointer).
+    InvalidPointer,
+    /// See [`Error::BadAlignment`](Error::BadAlignment).
+    BadAlignment,
+    /// See [`Error::ZeroSizedType`](Error::ZeroSizedType).
+    ZeroSizedType,
+    /// See [`Error::SizeMismatch`](Error::SizeMismatch).
+    SizeMismatch,
+    /// See [`Error::SearchMatchesEverything`](Error::SearchMatchesEverything).
+    SearchMatchesEverything,
+    /// See [`Error::Overflow`](Error::Overflow).
+    Overflow,
+    /// See [`Error::NonZeroPadding`](Error::NonZeroPadding).
+    NonZeroPadding,
+    /// See [`Error::UnpatchedReservation`](Error::UnpatchedReservation).
+    UnpatchedReservation,
+    /// See [`Error::UnresolvedLabel`](Error::UnresolvedLabel).
+    UnresolvedLabel,
+    /// See [`Error::RegionBorrowed`](Error::RegionBorrowed).
+    RegionBorrowed,
+    /// See [`Error::OverlappingRanges`](Error::OverlappingRanges).
+    OverlappingRanges,
+}
+
+/// Describes what was being done when an [`Error::Context`](Error::Context) error occurred.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ErrorContext {
+    /// A buffer operation, such as `get_ref`, `write` or `search`. *type_name* is the type being
+    /// cast, if any, *offset* is the requested offset, if any, and *length* is the requested number of bytes.
+    Operation {
+        name: &'static str,
+        type_name: Option<&'static str>,
+        offset: Option<usize>,
+        length: usize,
+    },
+    /// A message supplied with [`Error::context`](Error::context) or [`ResultExt::context`](ResultExt::context).
+    Message(String),
+}
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Operation { name, type_name, offset, length } => {
+                write!(f, "{}", name)?;
+                if let Some(type_name) = type_name { write!(f, "::<{}>", type_name)?; }
+                if let Some(offset) = offset { write!(f, " at {:#x}", offset)?; }
+                write!(f, " ({:#x} bytes)", length)
+            },
+            Self::Message(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Extends [`Result`](Result) objects with [`Error::context`](Error::context).
+pub trait ResultExt<T> {
+    /// Wrap the error, if any, with the given context message.
+    fn context<C: Into<String>>(self, context: C) -> Result<T, Error>;
+    /// Wrap the error, if any, with the context message returned by *f*. Unlike
+    /// [`ResultExt::context`](ResultExt::context), the message is only built on failure.
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, Error>;
+}
+impl<T> ResultExt<T> for Result<T, Error> {
+    fn context<C: Into<String>>(self, context: C) -> Result<T, Error> {
+        self.map_err(|error| error.context(context))
+    }
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, Error> {
+        self.map_err(|error| error.context(f()))
+    }
+}
 
 /// Convert the given reference of type ```T``` to a [`u8`](u8) [slice](slice).
 pub fn ref_to_bytes<T: Castable>(data: &T) -> Result<&[u8], Error> {
@@ -0,0 +1,111 @@
+use crate::Error;
+use memchr::memmem::Finder;
+use rayon::prelude::*;
+
+/// The smallest chunk handed to a thread. Smaller chunks cost more in scheduling than they save.
+const MIN_CHUNK_SIZE: usize = 0x10000;
+
+/// Split *data* into chunks and call *scan* on each of them in parallel, concatenating the results in order.
+///
+/// Each chunk owns the match offsets from its start up to the start of the next chunk, but the slice given to
+/// *scan* extends *overlap* bytes further so that matches crossing into the next chunk are still seen. *scan*
+/// gets that slice, the offset of the chunk and the number of offsets the chunk owns. The last chunk also owns
+/// the offset at the end of the data, which only an empty pattern can match.
+fn scan_chunks<T, F>(data: &[u8], overlap: usize, scan: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&[u8], usize, usize) -> Vec<T> + Sync,
+{
+    let chunks = rayon::current_num_threads().saturating_mul(4);
+    let chunk_size = std::cmp::max(MIN_CHUNK_SIZE, data.len().div_ceil(chunks));
+    let count = std::cmp::max(1, data.len().div_ceil(chunk_size));
+
+    (0..count).into_par_iter()
+        .flat_map_iter(|index| {
+            let start = index * chunk_size;
+            let end = std::cmp::min(start + chunk_size, data.len());
+            let window = std::cmp::min(end.saturating_add(overlap), data.len());
+            let owned = if end == data.len() { end - start + 1 } else { end - start };
+
+            scan(&data[start..window], start, owned)
+        })
+        .collect()
+}
+
+/// Find every offset below *owned* at which *finder* matches *window*, including overlapping matches.
+fn find_overlapping(finder: &Finder<'_>, window: &[u8], owned: usize) -> Vec<usize> {
+    let mut offsets = Vec::<usize>::new();
+    let mut position = 0usize;
+
+    while position < owned && position <= window.len() {
+        let found = match finder.find(&window[position..]) {
+            Some(index) => position + index,
+            None => break,
+        };
+
+        if found >= owned { break; }
+
+        offsets.push(found);
+        position = found + 1;
+    }
+
+    offsets
+}
+
+pub(crate) fn par_search(data: &[u8], term: &[u8]) -> Result<Vec<usize>, Error> {
+    if term.len() > data.len() { return Err(Error::OutOfBounds(data.len(),term.len())); }
+
+    let finder = Finder::new(term);
+
+    Ok(scan_chunks(data, term.len().saturating_sub(1), |window, base, owned| {
+        find_overlapping(&finder, window, owned).into_iter().map(|offset| base + offset).collect()
+    }))
+}
+
+pub(crate) fn par_search_dynamic(data: &[u8], term: &[Option<u8>]) -> Result<Vec<usize>, Error> {
+    if term.len() > data.len() { return Err(Error::OutOfBounds(data.len(),term.len())); }
+
+    let (anchor, value) = match term.iter().enumerate().find_map(|(index, byte)| byte.map(|value| (index, value))) {
+        Some(anchor) => anchor,
+        None => return Err(Error::SearchMatchesEverything),
+    };
+
+    Ok(scan_chunks(data, term.len() - 1, |window, base, owned| {
+        if window.len() < term.len() { return Vec::new(); }
+
+        // find the first constant byte, then check the rest of the pattern around it
+        let candidates = std::cmp::min(owned, window.len() - term.len() + 1);
+
+        memchr::memchr_iter(value, &window[anchor..anchor + candidates])
+            .filter(|&offset| {
+                term.iter()
+                    .zip(&window[offset..offset + term.len()])
+                    .all(|(expected, actual)| expected.map_or(true, |expected| expected == *actual))
+            })
+            .map(|offset| base + offset)
+            .collect()
+    }))
+}
+
+pub(crate) fn par_search_many<B: AsRef<[u8]>>(data: &[u8], terms: &[B]) -> Result<Vec<(usize, usize)>, Error> {
+    let terms = terms.iter().map(AsRef::as_ref).collect::<Vec<&[u8]>>();
+
+    if let Some(longest) = terms.iter().map(|term| term.len()).max() {
+        if longest > data.len() { return Err(Error::OutOfBounds(data.len(),longest)); }
+    }
+
+    let finders = terms.iter().map(Finder::new).collect::<Vec<Finder<'_>>>();
+    let overlap = terms.iter().map(|term| term.len().saturating_sub(1)).max().unwrap_or(0);
+
+    Ok(scan_chunks(data, overlap, |window, base, owned| {
+        let mut matches = finders.iter()
+            .enumerate()
+            .flat_map(|(index, finder)| {
+                find_overlapping(finder, window, owned).into_iter().map(move |offset| (base + offset, index))
+            })
+            .collect::<Vec<(usize, usize)>>();
+
+        matches.sort_unstable();
+        matches
+    }))
+}
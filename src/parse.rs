@@ -0,0 +1,166 @@
+use crate::{Buffer, Castable, Error, VecBuffer};
+use std::convert::{TryFrom, TryInto};
+
+/// Objects which can be parsed out of and written into a buffer, but whose size may vary.
+///
+/// Where [`Castable`](Castable) objects have a fixed size and can be referenced directly within a buffer,
+/// `BufferParse` objects are copied out of the buffer piece by piece. This allows for records such as
+/// "a header followed by *count* entries" or "a length-prefixed name". Every `Castable` object is also
+/// `BufferParse`, so fixed-size objects can be used as the fields of variable-length records.
+///
+/// This trait can be implemented with [the BufferParse derive macro](pkbuffer_derive::BufferParse).
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferParse, Castable, VecBuffer};
+///
+/// #[repr(C)]
+/// #[derive(Copy, Clone, Castable)]
+/// struct Header {
+///     magic: u16,
+///     entries: u16,
+/// }
+///
+/// #[derive(BufferParse)]
+/// struct Record {
+///     header: Header,
+///     #[count = "header.entries"]
+///     entries: Vec<u32>,
+///     #[len_prefix(u8)]
+///     name: Vec<u8>,
+/// }
+///
+/// let record = Record {
+///     header: Header { magic: 0x4B50, entries: 2 },
+///     entries: vec![0xDEADBEEF, 0xFACEBABE],
+///     name: b"pk".to_vec(),
+/// };
+///
+/// let mut buffer = VecBuffer::new();
+/// assert!(record.write_into(&mut buffer).is_ok());
+/// assert_eq!(buffer.len(), 4 + 8 + 1 + 2);
+///
+/// let (parsed, size) = Record::parse(&buffer, 0).unwrap();
+/// assert_eq!(size, buffer.len());
+/// assert_eq!(parsed.entries, [0xDEADBEEF, 0xFACEBABE]);
+/// assert_eq!(parsed.name, b"pk");
+/// ```
+pub trait BufferParse: Sized {
+    /// Parse an object out of the *buffer* at the given *offset*.
+    ///
+    /// On success, returns the object and the number of bytes it occupies within the buffer.
+    fn parse<B: Buffer + ?Sized>(buffer: &B, offset: usize) -> Result<(Self, usize), Error>;
+    /// Write this object to the end of the given [`VecBuffer`](VecBuffer).
+    fn write_into(&self, buffer: &mut VecBuffer) -> Result<(), Error>;
+}
+impl<T: Castable> BufferParse for T {
+    fn parse<B: Buffer + ?Sized>(buffer: &B, offset: usize) -> Result<(Self, usize), Error> {
+        Ok((buffer.read_val::<T>(offset)?, std::mem::size_of::<T>()))
+    }
+    fn write_into(&self, buffer: &mut VecBuffer) -> Result<(), Error> {
+        buffer.append_ref::<T>(self)
+    }
+}
+
+/// Parse *count* consecutive objects of type *T* out of the *buffer* at the given *offset*.
+///
+/// On success, returns the objects and the number of bytes they occupy within the buffer.
+pub fn parse_vec<T: BufferParse, B: Buffer + ?Sized>(buffer: &B, offset: usize, count: usize) -> Result<(Vec<T>, usize), Error> {
+    // don't trust the count with a preallocation, it typically comes from the data being parsed
+    let mut result = Vec::<T>::new();
+    let mut cursor = offset;
+
+    for _ in 0..count {
+        let (item, size) = T::parse(buffer, cursor)?;
+        result.push(item);
+        cursor = match cursor.checked_add(size) {
+            Some(next) => next,
+            None => return Err(Error::Overflow(cursor)),
+        };
+    }
+
+    Ok((result, cursor - offset))
+}
+
+/// Write the given slice of objects of type *T* to the end of the given [`VecBuffer`](VecBuffer).
+pub fn write_vec<T: BufferParse>(data: &[T], buffer: &mut VecBuffer) -> Result<(), Error> {
+    for item in data {
+        item.write_into(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Parse a length prefix of type *P* out of the *buffer* at the given *offset* and convert it to a [`usize`](usize).
+///
+/// Returns an [`Error::Overflow`](Error::Overflow) error if the length doesn't fit in a `usize`.
+pub fn parse_len_prefix<P, B>(buffer: &B, offset: usize) -> Result<(usize, usize), Error>
+where
+    P: Castable + TryInto<usize>,
+    B: Buffer + ?Sized,
+{
+    let prefix = buffer.read_val::<P>(offset)?;
+
+    match prefix.try_into() {
+        Ok(len) => Ok((len, std::mem::size_of::<P>())),
+        Err(_) => Err(Error::Overflow(usize::MAX)),
+    }
+}
+
+/// Write the given length as a length prefix of type *P* to the end of the given [`VecBuffer`](VecBuffer).
+///
+/// Returns an [`Error::Overflow`](Error::Overflow) error if the length doesn't fit in type *P*.
+pub fn write_len_prefix<P>(len: usize, buffer: &mut VecBuffer) -> Result<(), Error>
+where
+    P: Castable + TryFrom<usize>,
+{
+    match P::try_from(len) {
+        Ok(prefix) => buffer.append_ref::<P>(&prefix),
+        Err(_) => Err(Error::Overflow(len)),
+    }
+}
+
+/// Integers which can be used as the count or offset expression of a [`BufferParse`](BufferParse) field.
+///
+/// When writing, the fields of the record are borrowed, so an expression such as `#[count = "count"]`
+/// evaluates to a reference. This trait accepts integers and references to them alike.
+pub trait ParseIndex {
+    /// Convert this value to a [`usize`](usize).
+    ///
+    /// Returns an [`Error::Overflow`](Error::Overflow) error if the value is negative or doesn't fit in a `usize`,
+    /// since these values typically come from the data being parsed.
+    fn parse_index(&self) -> Result<usize, Error>;
+}
+impl<T: ParseIndex + ?Sized> ParseIndex for &T {
+    fn parse_index(&self) -> Result<usize, Error> {
+        (**self).parse_index()
+    }
+}
+
+macro_rules! impl_parse_index {
+    ($($ty:ty),*) => {
+        $(
+            impl ParseIndex for $ty {
+                fn parse_index(&self) -> Result<usize, Error> {
+                    match usize::try_from(*self) {
+                        Ok(index) => Ok(index),
+                        Err(_) => Err(Error::Overflow(usize::MAX)),
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_parse_index!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Round the given *offset* up to the next multiple of *alignment*.
+///
+/// Returns an [`Error::Overflow`](Error::Overflow) error if the aligned offset doesn't fit in a `usize`
+/// or the alignment is zero.
+pub fn align_offset(offset: usize, alignment: usize) -> Result<usize, Error> {
+    match offset.checked_next_multiple_of(alignment) {
+        Some(aligned) => Ok(aligned),
+        None => Err(Error::Overflow(offset)),
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{Castable, Error, ref_to_bytes};
+use memchr::memmem;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+
+/// A region of mapped memory within a process, as listed in `/proc/<pid>/maps`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MemoryRegion {
+    /// The start address of the region.
+    pub start: usize,
+    /// The end address of the region, exclusive.
+    pub end: usize,
+    /// Whether or not the region is readable.
+    pub readable: bool,
+    /// Whether or not the region is writable.
+    pub writable: bool,
+    /// Whether or not the region is executable.
+    pub executable: bool,
+    /// The offset into the mapped file.
+    pub offset: usize,
+    /// The path of the mapped file or pseudo-path (e.g., `[stack]`) of the region, if any.
+    pub path: Option<String>,
+}
+impl MemoryRegion {
+    /// Get the size of this region.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    /// Check whether or not this region is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// Check whether or not the given *address* is within this region.
+    pub fn contains(&self, address: usize) -> bool {
+        address >= self.start && address < self.end
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let (start, end) = fields.next()?.split_once('-')?;
+        let permissions = fields.next()?.as_bytes();
+        let offset = fields.next()?;
+        let _device = fields.next()?;
+        let _inode = fields.next()?;
+        let path = fields.collect::<Vec<&str>>().join(" ");
+
+        Some(Self {
+            start: usize::from_str_radix(start, 16).ok()?,
+            end: usize::from_str_radix(end, 16).ok()?,
+            readable: permissions.first() == Some(&b'r'),
+            writable: permissions.get(1) == Some(&b'w'),
+            executable: permissions.get(2) == Some(&b'x'),
+            offset: usize::from_str_radix(offset, 16).ok()?,
+            path: if path.is_empty() { None } else { Some(path) },
+        })
+    }
+}
+
+/// A buffer-like view into the memory of another process.
+///
+/// Memory is accessed through `/proc/<pid>/mem`, so the caller needs permission to trace the
+/// target process (e.g., being its parent or having `CAP_SYS_PTRACE`). Because the memory lives
+/// in another address space, references cannot be handed out; instead, objects are copied into
+/// owned values. Reads go through a cache of *chunk*-sized, chunk-aligned blocks. The target
+/// process keeps running while it is being examined, so call
+/// [`ProcessMemoryBuffer::clear_cache`](ProcessMemoryBuffer::clear_cache) when its memory may have changed.
+///
+/// Offsets given to this object are addresses within the target process.
+///
+/// # Example
+/// ```rust,no_run
+/// use pkbuffer::ProcessMemoryBuffer;
+///
+/// let child = std::process::Command::new("sleep").arg("10").spawn().unwrap();
+/// let process = ProcessMemoryBuffer::open(child.id()).unwrap();
+/// let image = process.regions().unwrap().into_iter().find(|r| r.offset == 0 && r.path.is_some()).unwrap();
+///
+/// assert_eq!(process.read(image.start, 4).unwrap(), b"\x7fELF");
+/// assert_eq!(process.get_ref::<u8>(image.start).unwrap(), 0x7F);
+/// ```
+#[derive(Debug)]
+pub struct ProcessMemoryBuffer {
+    pid: u32,
+    file: File,
+    chunk_size: usize,
+    cache_capacity: usize,
+    cache: RefCell<HashMap<usize, Vec<u8>>>,
+    cache_order: RefCell<VecDeque<usize>>,
+}
+impl ProcessMemoryBuffer {
+    /// The default size of the chunks read and cached.
+    pub const DEFAULT_CHUNK_SIZE: usize = 0x1000;
+    /// The default number of chunks cached.
+    pub const DEFAULT_CACHE_CAPACITY: usize = 0x100;
+
+    /// Open the memory of the process with the given *pid* for reading.
+    pub fn open(pid: u32) -> Result<Self, Error> {
+        let file = File::open(format!("/proc/{}/mem", pid))?;
+        Ok(Self::from_file(pid, file))
+    }
+    /// Open the memory of the process with the given *pid* for reading and writing.
+    pub fn open_writable(pid: u32) -> Result<Self, Error> {
+        let file = OpenOptions::new().read(true).write(true).open(format!("/proc/{}/mem", pid))?;
+        Ok(Self::from_file(pid, file))
+    }
+    fn from_file(pid: u32, file: File) -> Self {
+        Self {
+            pid,
+            file,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            cache_capacity: Self::DEFAULT_CACHE_CAPACITY,
+            cache: RefCell::new(HashMap::new()),
+            cache_order: RefCell::new(VecDeque::new()),
+        }
+    }
+    /// Get the process ID of the process being examined.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+    /// Get the size of the chunks read into the cache.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+    /// Set the size of the chunks read into the cache. This clears the cache.
+    ///
+    /// The chunk size should be a multiple of the page size, otherwise chunks can straddle
+    /// mapped and unmapped memory and fail to read.
+    ///
+    /// # Panics
+    /// Panics if *chunk_size* is zero.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+
+        self.chunk_size = chunk_size;
+        self.clear_cache();
+    }
+    /// Get the maximum number of chunks kept in the cache.
+    pub fn cache_capacity(&self) -> usize {
+        self.cache_capacity
+    }
+    /// Set the maximum number of chunks kept in the cache. A capacity of zero disables the cache.
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cache_capacity = capacity;
+        self.clear_cache();
+    }
+    /// Discard all cached chunks, forcing subsequent reads to fetch fresh data from the process.
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+        self.cache_order.borrow_mut().clear();
+    }
+    /// Get the mapped memory regions of the process, as listed in `/proc/<pid>/maps`.
+    pub fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        let maps = std::fs::read_to_string(format!("/proc/{}/maps", self.pid))?;
+        Ok(maps.lines().filter_map(MemoryRegion::parse).collect())
+    }
+
+    fn read_uncached(&self, address: usize, size: usize) -> Result<Vec<u8>, Error> {
+        let mut data = vec![0u8; size];
+        self.file.read_exact_at(&mut data, address as u64)?;
+        Ok(data)
+    }
+    fn read_chunk_into(&self, base: usize, start: usize, dest: &mut [u8]) -> Result<(), Error> {
+        if let Some(chunk) = self.cache.borrow().get(&base) {
+            if start+dest.len() <= chunk.len() {
+                dest.copy_from_slice(&chunk[start..start+dest.len()]);
+                return Ok(());
+            }
+        }
+
+        let mut chunk = vec![0u8; self.chunk_size];
+        let read = self.file.read_at(&mut chunk, base as u64)?;
+        chunk.truncate(read);
+
+        if start+dest.len() > chunk.len() {
+            return Err(Error::from(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)));
+        }
+
+        dest.copy_from_slice(&chunk[start..start+dest.len()]);
+
+        if self.cache_capacity > 0 {
+            let mut cache = self.cache.borrow_mut();
+            let mut order = self.cache_order.borrow_mut();
+
+            while order.len() >= self.cache_capacity {
+                if let Some(oldest) = order.pop_front() { cache.remove(&oldest); }
+            }
+
+            cache.insert(base, chunk);
+            order.push_back(base);
+        }
+
+        Ok(())
+    }
+    /// Read *size* bytes from the given *address* in the process.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the read wraps around the address space and
+    /// an [`Error::IoError`](Error::IoError) error if the memory could not be read.
+    pub fn read(&self, address: usize, size: usize) -> Result<Vec<u8>, Error> {
+        let end = match address.checked_add(size) {
+            Some(end) => end,
+            None => return Err(Error::OutOfBounds(usize::MAX,address)),
+        };
+
+        let mut data = vec![0u8; size];
+        let mut current = address;
+
+        while current < end {
+            let base = current - (current % self.chunk_size);
+            let start = current - base;
+            let amount = std::cmp::min(self.chunk_size - start, end - current);
+            let offset = current - address;
+
+            self.read_chunk_into(base, start, &mut data[offset..offset+amount])?;
+            current += amount;
+        }
+
+        Ok(data)
+    }
+    /// Read a copy of an object of type *T* at the given *address*.
+    ///
+    /// The address need not be aligned. See [`ProcessMemoryBuffer::read`](ProcessMemoryBuffer::read) for errors.
+    pub fn get_ref<T: Castable>(&self, address: usize) -> Result<T, Error> {
+        let data = self.read(address, std::mem::size_of::<T>())?;
+        Ok(unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) })
+    }
+    /// Read a copy of *count* objects of type *T* starting at the given *address*.
+    ///
+    /// See [`ProcessMemoryBuffer::read`](ProcessMemoryBuffer::read) for errors.
+    pub fn get_slice_ref<T: Castable>(&self, address: usize, count: usize) -> Result<Vec<T>, Error> {
+        let size = std::mem::size_of::<T>();
+        let total = match size.checked_mul(count) {
+            Some(total) => total,
+            None => return Err(Error::OutOfBounds(usize::MAX,usize::MAX)),
+        };
+        let data = self.read(address, total)?;
+
+        Ok((0..count)
+           .map(|i| unsafe { std::ptr::read_unaligned(data.as_ptr().add(i * size) as *const T) })
+           .collect())
+    }
+    /// Write the given *data* to the given *address* in the process.
+    ///
+    /// The buffer must have been opened with [`ProcessMemoryBuffer::open_writable`](ProcessMemoryBuffer::open_writable).
+    /// Returns an [`Error::IoError`](Error::IoError) error if the memory could not be written, and an
+    /// [`Error::OutOfBounds`](Error::OutOfBounds) error if the write wraps around the address space.
+    pub fn write<B: AsRef<[u8]>>(&mut self, address: usize, data: B) -> Result<(), Error> {
+        let buf = data.as_ref();
+        let end = match address.checked_add(buf.len()) {
+            Some(end) => end,
+            None => return Err(Error::OutOfBounds(usize::MAX,address)),
+        };
+
+        self.file.write_all_at(buf, address as u64)?;
+
+        let first = address - (address % self.chunk_size);
+        let cache = self.cache.get_mut();
+        self.cache_order.get_mut().retain(|base| {
+            let stale = *base >= first && *base < end;
+            if stale { cache.remove(base); }
+            !stale
+        });
+
+        Ok(())
+    }
+    /// Write the given object of type *T* to the given *address* in the process.
+    ///
+    /// See [`ProcessMemoryBuffer::write`](ProcessMemoryBuffer::write) for errors.
+    pub fn write_ref<T: Castable>(&mut self, address: usize, data: &T) -> Result<(), Error> {
+        let bytes = ref_to_bytes::<T>(data)?;
+        self.write(address, bytes)
+    }
+    /// Search for the given [`u8`](u8) [slice](slice) *data* within the *size* bytes starting at the given *address*.
+    ///
+    /// On success, this returns the addresses of all matches. Returns an
+    /// [`Error::SearchMatchesEverything`](Error::SearchMatchesEverything) error if *data* is empty. The memory is scanned in pieces without
+    /// going through the cache, so large regions can be searched.
+    /// See [`ProcessMemoryBuffer::read`](ProcessMemoryBuffer::read) for errors.
+    pub fn search<B: AsRef<[u8]>>(&self, address: usize, size: usize, data: B) -> Result<Vec<usize>, Error> {
+        let term = data.as_ref();
+
+        if term.is_empty() { return Err(Error::SearchMatchesEverything); }
+        if term.len() > size { return Err(Error::OutOfBounds(size,term.len())); }
+
+        let end = match address.checked_add(size) {
+            Some(end) => end,
+            None => return Err(Error::OutOfBounds(usize::MAX,address)),
+        };
+
+        let piece_size = std::cmp::max(self.chunk_size * 16, term.len());
+        let finder = memmem::Finder::new(term);
+        let mut results = Vec::<usize>::new();
+        let mut piece_start = address;
+
+        while end - piece_start >= term.len() {
+            let piece_end = std::cmp::min(piece_start.saturating_add(piece_size + term.len() - 1), end);
+            let piece = self.read_uncached(piece_start, piece_end - piece_start)?;
+
+            results.extend(finder.find_iter(&piece)
+                           .filter(|offset| *offset < piece_size)
+                           .map(|offset| piece_start + offset));
+
+            piece_start = match piece_start.checked_add(piece_size) {
+                Some(next) if next <= end => next,
+                _ => break,
+            };
+        }
+
+        Ok(results)
+    }
+}
@@ -1,7 +1,18 @@
-use crate::{Buffer, Error};
+use crate::{Buffer, BufferMut, Error, SubBufferMut};
 
-/// A [`Buffer`](Buffer) object backed by a pointer/size pair. Use this buffer type
+/// A read-only [`Buffer`](Buffer) object backed by a pointer/size pair. Use this buffer type
 /// when accessing unowned memory or arbitrary allocated memory.
+///
+/// Since the memory may be read-only, such as a `.rodata` section or a read-only mapping,
+/// this buffer can't be written to. Use [`PtrBufferMut`](PtrBufferMut) for writable memory.
+///
+/// ```compile_fail
+/// use pkbuffer::{BufferMut, PtrBuffer};
+///
+/// let data = [0u8; 4];
+/// let mut buffer = PtrBuffer::new(data.as_ptr(), data.len());
+/// buffer.write_val::<u32>(0, 0xDEADBEEF).unwrap();
+/// ```
 #[derive(Copy, Clone, Eq, Debug)]
 pub struct PtrBuffer {
     pointer: *const u8,
@@ -27,8 +38,9 @@ impl PtrBuffer {
             return Err(Error::OutOfBounds(self.len(),offset));
         }
 
-        if offset+size > self.len() {
-            return Err(Error::OutOfBounds(self.len(),offset+size));
+        match offset.checked_add(size) {
+            Some(end) if end <= self.len() => (),
+            _ => return Err(Error::OutOfBounds(self.len(),offset.saturating_add(size))),
         }
 
         unsafe { Ok(Self::new(self.as_ptr().add(offset), size)) }
@@ -52,18 +64,10 @@ impl Buffer for PtrBuffer {
     fn as_ptr(&self) -> *const u8 {
         self.pointer
     }
-    /// Get the `PtrBuffer` object as a mutable pointer.
-    fn as_mut_ptr(&mut self) -> *mut u8 {
-        self.pointer as *mut u8
-    }
     /// Get the `PtrBuffer` object as a slice.
     fn as_slice(&self) -> &[u8] {
         unsafe { std::slice::from_raw_parts(self.pointer, self.size) }
     }
-    /// Get the `PtrBuffer` object as a mutable slice.
-    fn as_mut_slice(&mut self) -> &mut [u8] {
-        unsafe { std::slice::from_raw_parts_mut(self.as_mut_ptr(), self.size) }
-    }
 }
 impl PartialEq<[u8]> for PtrBuffer {
     fn eq(&self, other: &[u8]) -> bool {
@@ -92,21 +96,11 @@ impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::Index<Idx> for PtrBuffer {
         self.as_slice().index(index)
     }
 }
-impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::IndexMut<Idx> for PtrBuffer {
-    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
-        self.as_mut_slice().index_mut(index)
-    }
-}
 impl std::convert::AsRef<[u8]> for PtrBuffer {
     fn as_ref(&self) -> &[u8] {
         self.as_slice()
     }
 }
-impl std::convert::AsMut<[u8]> for PtrBuffer {
-    fn as_mut(&mut self) -> &mut [u8] {
-        self.as_mut_slice()
-    }
-}
 impl std::hash::Hash for PtrBuffer {
     fn hash<H>(&self, state: &mut H)
     where
@@ -129,3 +123,137 @@ impl std::iter::IntoIterator for PtrBuffer {
         self.to_vec().into_iter()
     }
 }
+
+/// A writable [`BufferMut`](BufferMut) object backed by a mutable pointer/size pair.
+///
+/// This is the writable counterpart of [`PtrBuffer`](PtrBuffer). Just like `PtrBuffer`, make sure the
+/// memory outlives the object and is actually writable. Since two of these objects pointing at the same
+/// memory would allow aliasing writes, this type is neither `Copy` nor `Clone`.
+#[derive(Eq, Debug)]
+pub struct PtrBufferMut {
+    pointer: *mut u8,
+    size: usize,
+}
+impl PtrBufferMut {
+    /// Create a new buffer object with a given mutable *pointer* and *size*. Just make sure the pointer outlives
+    /// the object and not the other way around.
+    pub fn new(pointer: *mut u8, size: usize) -> Self {
+        Self { pointer, size }
+    }
+    /// Set the new pointer of this buffer.
+    pub fn set_pointer(&mut self, pointer: *mut u8) {
+        self.pointer = pointer;
+    }
+    /// Set the new size of this buffer.
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size;
+    }
+    /// Get a read-only [`PtrBuffer`](PtrBuffer) of this buffer.
+    pub fn as_ptr_buffer(&self) -> PtrBuffer {
+        PtrBuffer::new(self.pointer, self.size)
+    }
+    /// Borrow a [`SubBufferMut`](SubBufferMut) of *size* bytes at the given *offset* within the current buffer.
+    ///
+    /// The sub-buffer borrows this buffer mutably, so the two can't be used to write the same bytes at the same time.
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the sub-buffer goes out of bounds of the buffer.
+    pub fn sub_buffer(&mut self, offset: usize, size: usize) -> Result<SubBufferMut<'_>, Error> {
+        if offset >= self.len() {
+            return Err(Error::OutOfBounds(self.len(),offset));
+        }
+
+        let end = match offset.checked_add(size) {
+            Some(end) if end <= self.len() => end,
+            _ => return Err(Error::OutOfBounds(self.len(),offset.saturating_add(size))),
+        };
+
+        Ok(SubBufferMut::new(&mut self.as_mut_slice()[offset..end], offset))
+    }
+    /// Split this buffer into two separate, non-overlapping buffers at the given splitpoint *mid*.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if this split goes out of bounds of the buffer.
+    pub fn split_at(self, mid: usize) -> Result<(Self, Self), Error> {
+        if mid > self.len() { return Err(Error::OutOfBounds(self.len(),mid)); }
+
+        Ok((Self::new(self.pointer,mid),
+            Self::new(unsafe { self.pointer.add(mid) }, self.len() - mid)))
+    }
+}
+impl Buffer for PtrBufferMut {
+    /// Get the length of this `PtrBufferMut` object.
+    fn len(&self) -> usize {
+        self.size
+    }
+    /// Get the `PtrBufferMut` object as a pointer.
+    fn as_ptr(&self) -> *const u8 {
+        self.pointer
+    }
+    /// Get the `PtrBufferMut` object as a slice.
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer, self.size) }
+    }
+}
+impl BufferMut for PtrBufferMut {
+    /// Get the `PtrBufferMut` object as a mutable pointer.
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.pointer
+    }
+    /// Get the `PtrBufferMut` object as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.pointer, self.size) }
+    }
+}
+impl From<PtrBufferMut> for PtrBuffer {
+    fn from(buffer: PtrBufferMut) -> Self {
+        buffer.as_ptr_buffer()
+    }
+}
+impl PartialEq<[u8]> for PtrBufferMut {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl<const N: usize> PartialEq<[u8; N]> for PtrBufferMut {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl PartialEq<Vec<u8>> for PtrBufferMut {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: Buffer> PartialEq<T> for PtrBufferMut {
+    fn eq(&self, other: &T) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::Index<Idx> for PtrBufferMut {
+    type Output = Idx::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::IndexMut<Idx> for PtrBufferMut {
+    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+        self.as_mut_slice().index_mut(index)
+    }
+}
+impl std::convert::AsRef<[u8]> for PtrBufferMut {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+impl std::convert::AsMut<[u8]> for PtrBufferMut {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+impl std::hash::Hash for PtrBufferMut {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: std::hash::Hasher
+    {
+        self.as_slice().hash(state);
+    }
+}
@@ -0,0 +1,220 @@
+//! [Serde](https://serde.rs) support, enabled with the `serde` feature.
+//!
+//! [`VecBuffer`](VecBuffer) serializes as a byte string. Formats without a native byte type,
+//! such as JSON, typically write that as an array of numbers. For something more compact in
+//! human-readable formats, use the [`hex`](hex) or [`base64`](base64) modules with serde's
+//! `with` attribute:
+//!
+//! ```rust
+//! use pkbuffer::VecBuffer;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Report {
+//!     #[serde(with = "pkbuffer::serialize::hex")]
+//!     header: VecBuffer,
+//!     #[serde(with = "pkbuffer::serialize::base64")]
+//!     body: VecBuffer,
+//! }
+//!
+//! let report = Report { header: VecBuffer::from_data(b"PK"), body: VecBuffer::from_data([0xFF, 0x00]) };
+//! let json = serde_json::to_string(&report).unwrap();
+//! assert_eq!(json, r#"{"header":"504b","body":"/wA="}"#);
+//!
+//! let report: Report = serde_json::from_str(&json).unwrap();
+//! assert_eq!(report.header, *b"PK");
+//! ```
+//!
+//! Non-human-readable formats always use raw bytes, even through these modules.
+
+use crate::{Buffer, Castable, PtrBuffer, VecBuffer, ref_to_bytes};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+impl Serialize for VecBuffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+impl<'de> Deserialize<'de> for VecBuffer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor).map(VecBuffer::from_data)
+    }
+}
+
+/// `PtrBuffer` objects can only be serialized, as there's nothing for them to point at when deserialized.
+/// Deserialize into a [`VecBuffer`](VecBuffer) instead.
+impl Serialize for PtrBuffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+/// A serialize-only wrapper around any [`Buffer`](Buffer) object, serializing its contents as bytes.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{VecBuffer, serialize::SerializeBuffer};
+///
+/// let buffer = VecBuffer::from_data([1, 2, 3]);
+/// assert_eq!(serde_json::to_string(&SerializeBuffer(&buffer)).unwrap(), "[1,2,3]");
+/// ```
+pub struct SerializeBuffer<'a, B: Buffer + ?Sized>(pub &'a B);
+impl<'a, B: Buffer + ?Sized> Serialize for SerializeBuffer<'a, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0.as_slice())
+    }
+}
+
+/// A wrapper which serializes a [`Castable`](Castable) object as its byte image.
+///
+/// Deserializing fails if the number of bytes doesn't match the size of *T*. Like the other
+/// serializers in this module, the bytes are written in native endianness.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Castable, serialize::ByteImage};
+///
+/// #[repr(C)]
+/// #[derive(Copy, Clone, Castable)]
+/// struct Header {
+///     magic: u16,
+///     version: u16,
+/// }
+///
+/// let header = ByteImage(Header { magic: 0x4B50, version: 1 });
+/// let json = serde_json::to_string(&header).unwrap();
+/// assert_eq!(json, "[80,75,1,0]");
+///
+/// let header: ByteImage<Header> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(header.0.magic, 0x4B50);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ByteImage<T: Castable>(pub T);
+impl<T: Castable> Serialize for ByteImage<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = ref_to_bytes::<T>(&self.0).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(bytes)
+    }
+}
+impl<'de, T: Castable> Deserialize<'de> for ByteImage<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserializer.deserialize_byte_buf(BytesVisitor)?;
+        let size = std::mem::size_of::<T>();
+
+        if bytes.len() != size { return Err(de::Error::invalid_length(bytes.len(), &format!("{} bytes", size).as_str())); }
+
+        Ok(ByteImage(VecBuffer::from_data(bytes).read_val::<T>(0).map_err(de::Error::custom)?))
+    }
+}
+
+struct BytesVisitor;
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a byte string or a sequence of bytes")
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut result = Vec::<u8>::with_capacity(seq.size_hint().unwrap_or(0).min(0x10000));
+
+        while let Some(byte) = seq.next_element::<u8>()? {
+            result.push(byte);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Deserialize a string with the given decoder in human-readable formats, or bytes otherwise.
+fn deserialize_encoded<'de, D, F>(deserializer: D, expecting: &'static str, decode: F) -> Result<VecBuffer, D::Error>
+where
+    D: Deserializer<'de>,
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    struct EncodedVisitor<F> {
+        expecting: &'static str,
+        decode: F,
+    }
+    impl<'de, F: Fn(&str) -> Option<Vec<u8>>> Visitor<'de> for EncodedVisitor<F> {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str(self.expecting)
+        }
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            (self.decode)(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self.expecting))
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(EncodedVisitor { expecting, decode }).map(VecBuffer::from_data)
+    }
+    else {
+        VecBuffer::deserialize(deserializer)
+    }
+}
+
+/// Serialize buffers as hex strings in human-readable formats, for use with `#[serde(with = "pkbuffer::serialize::hex")]`.
+///
+/// Lowercase hex is written, and either case is accepted when reading.
+pub mod hex {
+    use super::*;
+
+    /// Encode the given data as a lowercase hex string.
+    pub fn encode(data: &[u8]) -> String {
+        ::hex::encode(data)
+    }
+    /// Decode the given hex string, returning `None` if it isn't valid hex.
+    pub fn decode(data: &str) -> Option<Vec<u8>> {
+        ::hex::decode(data).ok()
+    }
+    /// Serialize the given buffer.
+    pub fn serialize<B: Buffer + ?Sized, S: Serializer>(buffer: &B, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() { serializer.serialize_str(&encode(buffer.as_slice())) }
+        else { serializer.serialize_bytes(buffer.as_slice()) }
+    }
+    /// Deserialize a buffer.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VecBuffer, D::Error> {
+        deserialize_encoded(deserializer, "a hex string", decode)
+    }
+}
+
+/// Serialize buffers as base64 strings in human-readable formats, for use with `#[serde(with = "pkbuffer::serialize::base64")]`.
+///
+/// The standard alphabet with padding is written. Padding is optional when reading.
+pub mod base64 {
+    use super::*;
+    use ::base64::Engine;
+    use ::base64::alphabet::STANDARD;
+    use ::base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    /// Encode the given data as a padded base64 string.
+    pub fn encode(data: &[u8]) -> String {
+        ENGINE.encode(data)
+    }
+    /// Decode the given base64 string, returning `None` if it isn't valid base64.
+    pub fn decode(data: &str) -> Option<Vec<u8>> {
+        ENGINE.decode(data).ok()
+    }
+    /// Serialize the given buffer.
+    pub fn serialize<B: Buffer + ?Sized, S: Serializer>(buffer: &B, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() { serializer.serialize_str(&encode(buffer.as_slice())) }
+        else { serializer.serialize_bytes(buffer.as_slice()) }
+    }
+    /// Deserialize a buffer.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VecBuffer, D::Error> {
+        deserialize_encoded(deserializer, "a base64 string", decode)
+    }
+}
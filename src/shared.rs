@@ -0,0 +1,215 @@
+use crate::{Buffer, BufferMut, Error, VecBuffer};
+use crate::buffer::resolve_range;
+use std::cell::UnsafeCell;
+use std::ops::{Range, RangeBounds};
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug)]
+struct Borrow {
+    id: usize,
+    range: Range<usize>,
+    mutable: bool,
+}
+
+#[derive(Debug, Default)]
+struct BorrowTable {
+    next_id: usize,
+    borrows: Vec<Borrow>,
+}
+
+/// An owned buffer which can be shared between threads, handing out views of its regions.
+///
+/// Regions are borrowed with [`SharedBuffer::region`](SharedBuffer::region) and
+/// [`SharedBuffer::region_mut`](SharedBuffer::region_mut), which follow the same rules as references, but
+/// per range of bytes and checked at runtime: any number of [`RegionGuard`](RegionGuard) objects may overlap, but
+/// a [`RegionGuardMut`](RegionGuardMut) can't overlap any other borrowed region. Borrowing a region which would
+/// break these rules returns an [`Error::RegionBorrowed`](Error::RegionBorrowed) error. Regions are released when
+/// their guards are dropped. Empty regions never conflict.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferMut, SharedBuffer};
+///
+/// let shared = SharedBuffer::from_data([0u8; 8]);
+///
+/// std::thread::scope(|scope| {
+///     let mut first = shared.region_mut(..4).unwrap();
+///     let mut second = shared.region_mut(4..).unwrap();
+///     assert!(shared.region(2..6).is_err());
+///
+///     scope.spawn(move || first.write_val::<u32>(0, 0xDEADBEEF).unwrap());
+///     scope.spawn(move || second.fill(0xFF));
+/// });
+///
+/// assert_eq!(shared.region(..).unwrap().as_slice(), [0xEF, 0xBE, 0xAD, 0xDE, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[derive(Debug)]
+pub struct SharedBuffer {
+    data: Box<[UnsafeCell<u8>]>,
+    table: Mutex<BorrowTable>,
+}
+// SAFETY: the bytes are only reached through guards, and the borrow table guarantees that a
+// mutable guard never overlaps another guard, just like the rules for references.
+unsafe impl Sync for SharedBuffer {}
+
+impl SharedBuffer {
+    /// Create a new `SharedBuffer` object from the given data.
+    pub fn from_data<B: AsRef<[u8]>>(data: B) -> Self {
+        Self {
+            data: data.as_ref().iter().map(|byte| UnsafeCell::new(*byte)).collect(),
+            table: Mutex::new(BorrowTable::default()),
+        }
+    }
+    /// Get the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Check whether or not the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Get the whole buffer as a mutable slice. This needs no runtime checks, since no regions can be
+    /// borrowed while the buffer is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        // SAFETY: the exclusive reference guarantees there are no guards
+        unsafe { std::slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+    /// Convert this buffer into a [`VecBuffer`](VecBuffer).
+    pub fn into_vec_buffer(mut self) -> VecBuffer {
+        VecBuffer::from_data(self.get_mut())
+    }
+    /// Borrow the given *range* of the buffer as read-only.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds, an
+    /// [`Error::Overflow`](Error::Overflow) error if one of its bounds overflows and an
+    /// [`Error::RegionBorrowed`](Error::RegionBorrowed) error if it overlaps a mutably borrowed region.
+    pub fn region<R: RangeBounds<usize>>(&self, range: R) -> Result<RegionGuard<'_>, Error> {
+        let (id, range) = self.borrow(range, false)?;
+        Ok(RegionGuard { owner: self, id, pointer: self.as_mut_ptr().wrapping_add(range.start), range })
+    }
+    /// Borrow the given *range* of the buffer as mutable.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the range is out of bounds, an
+    /// [`Error::Overflow`](Error::Overflow) error if one of its bounds overflows and an
+    /// [`Error::RegionBorrowed`](Error::RegionBorrowed) error if it overlaps any borrowed region.
+    pub fn region_mut<R: RangeBounds<usize>>(&self, range: R) -> Result<RegionGuardMut<'_>, Error> {
+        let (id, range) = self.borrow(range, true)?;
+        Ok(RegionGuardMut { owner: self, id, pointer: self.as_mut_ptr().wrapping_add(range.start), range })
+    }
+    fn as_mut_ptr(&self) -> *mut u8 {
+        // UnsafeCell<u8> has the same layout as u8
+        UnsafeCell::raw_get(self.data.as_ptr())
+    }
+    fn lock(&self) -> MutexGuard<'_, BorrowTable> {
+        // the table is never left in an inconsistent state, so a poisoned lock is still usable
+        self.table.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    fn borrow<R: RangeBounds<usize>>(&self, range: R, mutable: bool) -> Result<(usize, Range<usize>), Error> {
+        let Range { start, end } = resolve_range(self.len(), range)?;
+
+        let mut table = self.lock();
+
+        // empty regions can't overlap anything
+        let conflict = start < end && table.borrows.iter().any(|borrow| {
+            (mutable || borrow.mutable) && borrow.range.start < end && start < borrow.range.end
+        });
+
+        if conflict { return Err(Error::RegionBorrowed(start,end)); }
+
+        let id = table.next_id;
+        table.next_id += 1;
+        table.borrows.push(Borrow { id, range: start..end, mutable });
+
+        Ok((id, start..end))
+    }
+    fn release(&self, id: usize) {
+        self.lock().borrows.retain(|borrow| borrow.id != id);
+    }
+}
+impl From<VecBuffer> for SharedBuffer {
+    fn from(buffer: VecBuffer) -> Self {
+        Self::from_data(buffer)
+    }
+}
+
+/// A read-only view of a region of a [`SharedBuffer`](SharedBuffer), released when dropped.
+#[derive(Debug)]
+pub struct RegionGuard<'a> {
+    owner: &'a SharedBuffer,
+    id: usize,
+    pointer: *const u8,
+    range: Range<usize>,
+}
+// SAFETY: the guard behaves like a &[u8] into the shared buffer
+unsafe impl Send for RegionGuard<'_> {}
+unsafe impl Sync for RegionGuard<'_> {}
+
+impl RegionGuard<'_> {
+    /// Get the offset of this region within the [`SharedBuffer`](SharedBuffer).
+    pub fn offset(&self) -> usize {
+        self.range.start
+    }
+}
+impl Buffer for RegionGuard<'_> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+    fn as_ptr(&self) -> *const u8 {
+        self.pointer
+    }
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: the borrow table guarantees no mutable guard overlaps this region
+        unsafe { std::slice::from_raw_parts(self.pointer, self.len()) }
+    }
+}
+impl Drop for RegionGuard<'_> {
+    fn drop(&mut self) {
+        self.owner.release(self.id);
+    }
+}
+
+/// A mutable view of a region of a [`SharedBuffer`](SharedBuffer), released when dropped.
+#[derive(Debug)]
+pub struct RegionGuardMut<'a> {
+    owner: &'a SharedBuffer,
+    id: usize,
+    pointer: *mut u8,
+    range: Range<usize>,
+}
+// SAFETY: the guard behaves like a &mut [u8] into the shared buffer
+unsafe impl Send for RegionGuardMut<'_> {}
+unsafe impl Sync for RegionGuardMut<'_> {}
+
+impl RegionGuardMut<'_> {
+    /// Get the offset of this region within the [`SharedBuffer`](SharedBuffer).
+    pub fn offset(&self) -> usize {
+        self.range.start
+    }
+}
+impl Buffer for RegionGuardMut<'_> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+    fn as_ptr(&self) -> *const u8 {
+        self.pointer
+    }
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: the borrow table guarantees no other guard overlaps this region
+        unsafe { std::slice::from_raw_parts(self.pointer, self.len()) }
+    }
+}
+impl BufferMut for RegionGuardMut<'_> {
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.pointer
+    }
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: the borrow table guarantees no other guard overlaps this region
+        unsafe { std::slice::from_raw_parts_mut(self.pointer, self.len()) }
+    }
+}
+impl Drop for RegionGuardMut<'_> {
+    fn drop(&mut self) {
+        self.owner.release(self.id);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{Buffer, BufferMut};
+
+/// A mutable view of a region of another buffer, as returned by [`BufferMut::split_regions_mut`](BufferMut::split_regions_mut)
+/// and [`BufferMut::split_many_mut`](BufferMut::split_many_mut).
+///
+/// The regions handed out by a split never overlap, so unlike [`PtrBufferMut`](crate::PtrBufferMut), these
+/// views can be written to and sent to other threads without `unsafe`.
+#[derive(Eq, Debug)]
+pub struct SubBufferMut<'a> {
+    data: &'a mut [u8],
+    offset: usize,
+}
+impl<'a> SubBufferMut<'a> {
+    /// Create a new sub-buffer over the given *data*, which starts at *offset* within its parent buffer.
+    pub fn new(data: &'a mut [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+    /// Get the offset of this region within the buffer it was split from.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Convert this sub-buffer into the mutable slice it views.
+    pub fn into_mut_slice(self) -> &'a mut [u8] {
+        self.data
+    }
+}
+impl Buffer for SubBufferMut<'_> {
+    /// Get the length of this `SubBufferMut` object.
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Get the `SubBufferMut` object as a pointer.
+    fn as_ptr(&self) -> *const u8 {
+        self.data.as_ptr()
+    }
+    /// Get the `SubBufferMut` object as a slice.
+    fn as_slice(&self) -> &[u8] {
+        self.data
+    }
+}
+impl BufferMut for SubBufferMut<'_> {
+    /// Get the `SubBufferMut` object as a mutable pointer.
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.data.as_mut_ptr()
+    }
+    /// Get the `SubBufferMut` object as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.data
+    }
+}
+impl PartialEq<[u8]> for SubBufferMut<'_> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl<const N: usize> PartialEq<[u8; N]> for SubBufferMut<'_> {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+impl PartialEq<Vec<u8>> for SubBufferMut<'_> {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: Buffer> PartialEq<T> for SubBufferMut<'_> {
+    fn eq(&self, other: &T) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::Index<Idx> for SubBufferMut<'_> {
+    type Output = Idx::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+impl<Idx: std::slice::SliceIndex<[u8]>> std::ops::IndexMut<Idx> for SubBufferMut<'_> {
+    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+        self.as_mut_slice().index_mut(index)
+    }
+}
+impl std::convert::AsRef<[u8]> for SubBufferMut<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+impl std::convert::AsMut<[u8]> for SubBufferMut<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+impl std::hash::Hash for SubBufferMut<'_> {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: std::hash::Hasher
+    {
+        self.as_slice().hash(state);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::Error;
+use memchr::memmem::Finder;
+use std::io::Read;
+
+/// An iterator which searches any [`Read`](std::io::Read) source for a pattern, yielding the absolute offset of
+/// every match.
+///
+/// The source is read in chunks, and the last `pattern.len() - 1` bytes of each chunk are carried over into the
+/// next one, so matches crossing a chunk boundary are still found without ever holding the whole source in memory.
+/// This makes it suitable for pipes and files larger than memory. Like [`Buffer::search_dynamic`](crate::Buffer::search_dynamic),
+/// overlapping matches are all returned. Offsets are [`u64`](u64), since the source may be larger than the address space.
+///
+/// Read errors are yielded as [`Error::IoError`](Error::IoError) errors, after which the iterator ends.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::StreamSearcher;
+///
+/// let data = [0xFFu8, 0xDE, 0xAD, 0xBE, 0xEF, 0xDE, 0xAD, 0xC0, 0xDE];
+///
+/// let mut searcher = StreamSearcher::new(&data[..], [0xDE, 0xAD]).unwrap();
+/// searcher.set_chunk_size(2);
+/// assert_eq!(searcher.collect::<Result<Vec<u64>, _>>().unwrap(), [1, 5]);
+///
+/// let searcher = StreamSearcher::new_dynamic(&data[..], [Some(0xDE), None, Some(0xBE)]).unwrap();
+/// assert_eq!(searcher.collect::<Result<Vec<u64>, _>>().unwrap(), [1]);
+/// ```
+#[derive(Debug)]
+pub struct StreamSearcher<R: Read> {
+    reader: R,
+    pattern: Vec<Option<u8>>,
+    anchor: usize,
+    finder: Option<Finder<'static>>,
+    window: Vec<u8>,
+    window_offset: u64,
+    position: usize,
+    chunk_size: usize,
+    eof: bool,
+}
+impl<R: Read> StreamSearcher<R> {
+    /// The number of bytes read at a time by default.
+    pub const DEFAULT_CHUNK_SIZE: usize = 0x10000;
+
+    /// Search the given *reader* for the given [`u8`](u8) [slice](slice). See [`Buffer::search`](crate::Buffer::search).
+    ///
+    /// Returns an [`Error::SearchMatchesEverything`](Error::SearchMatchesEverything) error if the search term is empty.
+    pub fn new<B: AsRef<[u8]>>(reader: R, data: B) -> Result<Self, Error> {
+        Self::new_dynamic(reader, data.as_ref().iter().copied().map(Some).collect::<Vec<Option<u8>>>())
+    }
+    /// Search the given *reader* for the given dynamic search term, where `None` is a wildcard. See
+    /// [`Buffer::search_dynamic`](crate::Buffer::search_dynamic).
+    ///
+    /// Returns an [`Error::SearchMatchesEverything`](Error::SearchMatchesEverything) error if the search term
+    /// is empty or made of wildcards only.
+    pub fn new_dynamic<B: AsRef<[Option<u8>]>>(reader: R, data: B) -> Result<Self, Error> {
+        let pattern = data.as_ref().to_vec();
+        let anchor = match pattern.iter().position(Option::is_some) {
+            Some(anchor) => anchor,
+            None => return Err(Error::SearchMatchesEverything),
+        };
+
+        // patterns without wildcards get the faster substring search
+        let finder = pattern.iter().copied().collect::<Option<Vec<u8>>>().map(|literal| Finder::new(&literal).into_owned());
+
+        Ok(Self {
+            reader,
+            pattern,
+            anchor,
+            finder,
+            window: Vec::new(),
+            window_offset: 0,
+            position: 0,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            eof: false,
+        })
+    }
+    /// Set the number of bytes read from the source at a time. Chunks are never smaller than one byte.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = std::cmp::max(1, chunk_size);
+    }
+    /// Get the number of bytes read from the source at a time.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+    /// Unwrap the underlying reader. Any bytes already read from it but not yet searched are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+    /// Find the next match starting at or after the current position within the window, moving past it.
+    fn find_in_window(&mut self) -> Option<usize> {
+        let length = self.pattern.len();
+        if self.window.len() < length { return None; }
+
+        // the last offset at which the whole pattern fits in the window
+        let last = self.window.len() - length;
+
+        while self.position <= last {
+            let found = match &self.finder {
+                Some(finder) => finder.find(&self.window[self.position..]).map(|index| self.position + index),
+                None => {
+                    let value = self.pattern[self.anchor].unwrap();
+                    memchr::memchr(value, &self.window[self.position + self.anchor..=last + self.anchor])
+                        .map(|index| self.position + index)
+                },
+            };
+
+            let found = match found {
+                Some(found) => found,
+                None => break,
+            };
+
+            self.position = found + 1;
+
+            let candidate = &self.window[found..found + length];
+            let matched = self.pattern.iter()
+                .zip(candidate)
+                .all(|(expected, actual)| expected.map_or(true, |expected| expected == *actual));
+
+            if matched { return Some(found); }
+        }
+
+        self.position = last + 1;
+        None
+    }
+    /// Drop the bytes which can no longer start a match and read the next chunk into the window.
+    fn refill(&mut self) -> Result<(), Error> {
+        let discard = std::cmp::min(self.position, self.window.len());
+        self.window.drain(..discard);
+        self.window_offset += discard as u64;
+        self.position -= discard;
+
+        let start = self.window.len();
+        self.window.resize(start + self.chunk_size, 0);
+
+        let result = loop {
+            match self.reader.read(&mut self.window[start..]) {
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+
+        let read = match result {
+            Ok(read) => read,
+            Err(err) => {
+                self.window.truncate(start);
+                self.eof = true;
+                return Err(Error::from(err));
+            },
+        };
+
+        self.window.truncate(start + read);
+        if read == 0 { self.eof = true; }
+
+        Ok(())
+    }
+}
+impl<R: Read> Iterator for StreamSearcher<R> {
+    type Item = Result<u64, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.find_in_window() {
+                return Some(Ok(self.window_offset + found as u64));
+            }
+
+            if self.eof { return None; }
+            if let Err(err) = self.refill() { return Some(Err(err)); }
+        }
+    }
+}
+impl<R: Read> std::iter::FusedIterator for StreamSearcher<R> {}
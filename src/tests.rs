@@ -1,5 +1,4 @@
 use pkbuffer::*;
-use hex;
 
 #[test]
 fn test_ptrbuffer() {
@@ -20,6 +19,7 @@ fn test_ptrbuffer() {
 
     #[repr(packed)]
     #[derive(Copy, Clone, Castable, Debug)]
+    #[allow(clippy::repr_packed_without_abi)]
     struct StructTest {
         deadbe: [u8; 3],
         efab: u16,
@@ -60,7 +60,7 @@ fn test_ptrbuffer() {
     let bad_alignment = buffer.get_ref::<AlignedTest>(2);
     assert!(bad_alignment.is_err());
 
-    if let Err(Error::BadAlignment(_,_)) = bad_alignment {}
+    if let Some(Error::BadAlignment(_,_)) = bad_alignment.err().as_ref().map(Error::root) {}
     else { panic!("didn't get an alignment error"); }
 
     let read_result = buffer.read(8, 4);
@@ -103,7 +103,7 @@ fn test_ptrbuffer() {
 
     let dynamic_test = hex::decode("ff2763582764ff276488654327384858642764").unwrap();
     let dynamic_buffer = PtrBuffer::new(dynamic_test.as_ptr(), dynamic_test.len());
-    let dynamic_search = dynamic_buffer.search_dynamic(&[None, Some(0x27), Some(0x64), None, Some(0x27), Some(0x64)]);
+    let dynamic_search = dynamic_buffer.search_dynamic([None, Some(0x27), Some(0x64), None, Some(0x27), Some(0x64)]);
 
     assert!(dynamic_search.is_ok());
 
@@ -124,13 +124,13 @@ fn test_vecbuffer() {
     let data = hex::decode("deadbeefabad1deadeadbea7defaced1").unwrap();
     let mut buffer = VecBuffer::from_data(&data);
 
-    assert!(buffer.write(0, &[0xFA, 0xCE, 0xBA, 0xBE]).is_ok());
+    assert!(buffer.write(0, [0xFA, 0xCE, 0xBA, 0xBE]).is_ok());
     assert!(!buffer.contains([0xDE, 0xAD, 0xBE, 0xEF]));
 
     assert!(buffer.write_ref::<u32>(4, &0xEFBEADDE).is_ok());
     assert!(buffer.contains_ref::<u32>(&0xEFBEADDE).unwrap());
 
-    buffer.append(&vec![0xAB, 0xAD, 0x1D, 0xEA]);
+    buffer.append([0xAB, 0xAD, 0x1D, 0xEA]);
     assert!(buffer.contains([0xAB, 0xAD, 0x1D, 0xEA]));
 
     let rhs = buffer.split_off(0x8);
@@ -145,3 +145,1111 @@ fn test_vecbuffer() {
 
     assert_eq!(buffer, hex::decode("facebabedeadbeefc0ffee74deadbea7defaced1abad1dea").unwrap());
 }
+
+#[test]
+fn test_checksum() {
+    let buffer = VecBuffer::from_data(b"123456789");
+
+    assert_eq!(buffer.checksum(.., Algorithm::Crc32).unwrap(), 0xCBF43926);
+    assert_eq!(buffer.checksum(.., Algorithm::Crc32c).unwrap(), 0xE3069283);
+    assert_eq!(buffer.checksum(.., Algorithm::Adler32).unwrap(), 0x091E01DE);
+    assert_eq!(buffer.checksum(0..0, Algorithm::Fnv1a32).unwrap(), 0x811C9DC5);
+    assert_eq!(buffer.checksum(0..1, Algorithm::Fnv1a64).unwrap(), 0xAF63AC4C86019AFC);
+    assert!(buffer.checksum(4..10, Algorithm::Crc32).is_err());
+    assert!(buffer.checksum(10.., Algorithm::Crc32).is_err());
+    assert_eq!(buffer.checksum(..=usize::MAX, Algorithm::Crc32).unwrap_err().root(), &Error::Overflow(usize::MAX));
+    assert_eq!(buffer.entropy((std::ops::Bound::Excluded(usize::MAX), std::ops::Bound::Unbounded)).unwrap_err().root(), &Error::Overflow(usize::MAX));
+
+    let ip_header = VecBuffer::from_data(hex::decode("0001f203f4f5f6f7").unwrap());
+    assert_eq!(ip_header.checksum(.., Algorithm::Internet).unwrap(), 0x220D);
+
+    // streaming across odd boundaries must match the one-shot checksum
+    let data = VecBuffer::from_data(hex::decode("deadbeefabad1deadeadbea7defaced1c0ffee").unwrap());
+
+    for algorithm in [Algorithm::Crc32, Algorithm::Adler32, Algorithm::Internet, Algorithm::Pe(4)] {
+        let mut checksum = Checksum::new(algorithm);
+        assert!(checksum.update_buffer(&data, ..3).is_ok());
+        assert!(checksum.update_buffer(&data, 3..8).is_ok());
+        assert!(checksum.update_buffer(&data, 8..).is_ok());
+        assert_eq!(checksum.len(), data.len());
+        assert_eq!(checksum.finish(), data.checksum(.., algorithm).unwrap());
+    }
+
+    // the PE checksum field is ignored
+    let mut zeroed = data.clone();
+    assert!(zeroed.write(4, [0u8; 4]).is_ok());
+    assert_eq!(zeroed.checksum(.., Algorithm::Pe(4)).unwrap(), data.checksum(.., Algorithm::Pe(4)).unwrap());
+    assert_eq!(zeroed.checksum(.., Algorithm::Pe(4)).unwrap(), 0xC097 + data.len() as u64);
+
+    #[cfg(feature = "sha2")]
+    assert_eq!(buffer.sha256(..).unwrap().to_vec(),
+               hex::decode("15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225").unwrap());
+}
+
+#[test]
+fn test_entropy() {
+    let data = (0..0x1000).map(|i| ((i * 7 + i / 3) % 251) as u8).collect::<Vec<u8>>();
+    let buffer = PtrBuffer::new(data.as_ptr(), data.len());
+
+    let histogram = buffer.byte_histogram(0..4).unwrap();
+    assert_eq!(histogram[0], 1);
+    assert_eq!(histogram[7], 1);
+    assert_eq!(histogram.iter().sum::<usize>(), 4);
+    assert!(buffer.byte_histogram(0..0x1001).is_err());
+
+    let zeroes = VecBuffer::with_initial_size(0x10);
+    assert_eq!(zeroes.entropy(..).unwrap(), 0.0);
+    assert_eq!(zeroes.entropy(0..0).unwrap(), 0.0);
+
+    // the incremental windows must agree with recalculating each window from scratch
+    for (window, step) in [(0x100, 0x10), (0x100, 0x100), (0x80, 0x200), (0x33, 0x7)] {
+        let mut count = 0;
+
+        for (offset, entropy) in buffer.windows_entropy(window, step) {
+            assert_eq!(offset, count * step);
+            assert!((entropy - buffer.entropy(offset..offset+window).unwrap()).abs() < 1e-9);
+            count += 1;
+        }
+
+        assert_eq!(count, (data.len() - window) / step + 1);
+    }
+
+    assert!(zeroes.windows_entropy(0x11, 1).next().is_none());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_process_memory() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+    let mut process = ProcessMemoryBuffer::open_writable(child.id()).unwrap();
+    assert_eq!(process.pid(), child.id());
+    assert_eq!(process.chunk_size(), ProcessMemoryBuffer::DEFAULT_CHUNK_SIZE);
+    assert_eq!(process.cache_capacity(), ProcessMemoryBuffer::DEFAULT_CACHE_CAPACITY);
+
+    // give the child a moment to finish mapping its image
+    let mut regions = process.regions().unwrap();
+    for _ in 0..100 {
+        if regions.iter().any(|r| r.path.as_deref().is_some_and(|p| p.starts_with('/'))) { break; }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        regions = process.regions().unwrap();
+    }
+
+    // every file mapped from its beginning in a freshly spawned process is an ELF image
+    let image = regions.iter().find(|r| r.readable && r.offset == 0 && r.path.as_deref().is_some_and(|p| p.starts_with('/'))).unwrap();
+    assert_eq!(process.read(image.start, 4).unwrap(), b"\x7fELF");
+    assert_eq!(process.get_ref::<u32>(image.start).unwrap(), 0x464C457F);
+    assert_eq!(process.get_ref::<u16>(image.start+1).unwrap(), 0x4C45);
+    assert_eq!(process.get_slice_ref::<u8>(image.start+1, 3).unwrap(), b"ELF");
+
+    let found = process.search(image.start, image.len(), b"\x7fELF").unwrap();
+    assert_eq!(found.first(), Some(&image.start));
+    assert!(process.search(image.start, image.len(), b"").is_err());
+
+    // reads spanning chunk boundaries go through multiple cached chunks
+    let header = process.read(image.start, 0x40).unwrap();
+    process.set_chunk_size(0x10);
+    assert_eq!(process.read(image.start+0xE, 0x14).unwrap(), header[0xE..0x22]);
+    process.set_cache_capacity(1);
+    assert_eq!(process.read(image.start+0x3, 0x30).unwrap(), header[0x3..0x33]);
+
+    let stack = regions.iter().find(|r| r.path.as_deref() == Some("[stack]")).unwrap();
+    let target = stack.end - 0x10;
+    let original = process.get_ref::<u64>(target).unwrap();
+    assert!(process.write_ref::<u64>(target, &0xFACEBABEDEADBEEF).is_ok());
+    assert_eq!(process.get_ref::<u64>(target).unwrap(), 0xFACEBABEDEADBEEF);
+    assert!(process.write_ref::<u64>(target, &original).is_ok());
+
+    assert!(process.read(0, 4).is_err());
+    assert_eq!(process.get_slice_ref::<u64>(image.start, usize::MAX), Err(Error::OutOfBounds(usize::MAX,usize::MAX)));
+    assert_eq!(process.write(usize::MAX, [0u8; 2]), Err(Error::OutOfBounds(usize::MAX,usize::MAX)));
+    assert!(process.search(usize::MAX - 8, 8, b"\x7fELF").is_err());
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_read_val() {
+    #[repr(C, packed)]
+    #[derive(Copy, Clone, Castable, Debug, PartialEq)]
+    struct PackedHeader {
+        magic: u16,
+        size: u32,
+        count: u16,
+    }
+
+    let mut buffer = VecBuffer::with_initial_size(0x11);
+    let header = PackedHeader { magic: 0x5A4D, size: 0xDEADBEEF, count: 3 };
+
+    // odd offsets are never aligned for the members of the header
+    assert!(buffer.write_val::<PackedHeader>(1, header).is_ok());
+    assert_eq!(buffer.read_val::<PackedHeader>(1).unwrap(), header);
+    assert_eq!(buffer.read_val::<u32>(3).unwrap(), 0xDEADBEEF);
+
+    assert!(buffer.write_vals::<u16>(9, &[0x1111, 0x2222, 0x3333, 0x4444]).is_ok());
+    assert_eq!(buffer.read_vals::<u16>(9, 4).unwrap(), [0x1111, 0x2222, 0x3333, 0x4444]);
+    assert_eq!(buffer.read_vals::<u32>(9, 2).unwrap(), [0x22221111, 0x44443333]);
+    assert!(buffer.write_vals::<u16>(0x11, &[]).is_ok());
+
+    assert!(buffer.read_val::<u32>(0xE).is_err());
+    assert!(buffer.read_vals::<u16>(9, 5).is_err());
+    assert_eq!(buffer.read_vals::<u64>(0, usize::MAX).unwrap_err().root(), &Error::Overflow(usize::MAX));
+    assert_eq!(buffer.read_vals::<u8>(1, usize::MAX).unwrap_err().root(), &Error::Overflow(1));
+    assert_eq!(buffer.read(1, usize::MAX).unwrap_err().root(), &Error::Overflow(1));
+    assert_eq!(buffer.get_slice_ref::<u16>(1, usize::MAX / 2 + 1).unwrap_err().root(), &Error::Overflow(1));
+    assert_eq!(buffer.get_mut_slice_ref::<u8>(1, usize::MAX).unwrap_err().root(), &Error::Overflow(1));
+    assert_eq!(unsafe { buffer.get_slice_ref_unaligned::<u8>(1, usize::MAX) }.unwrap_err().root(), &Error::Overflow(1));
+    assert!(buffer.write_val::<u32>(0xE, 0).is_err());
+    assert!(buffer.write_vals::<u16>(0xF, &[0, 0]).is_err());
+}
+
+#[test]
+fn test_typed_view() {
+    let data = hex::decode("ff0100000002000000030000000400000005000000").unwrap();
+    let mut buffer = VecBuffer::from_data(&data);
+
+    let view = buffer.view::<u32>(1, 5).unwrap();
+    assert_eq!(view.len(), 5);
+    assert_eq!(view.stride(), 4);
+    assert_eq!(view.first(), Some(1));
+    assert_eq!(view.last(), Some(5));
+    assert_eq!(view.get(5), None);
+    assert_eq!(view.iter().rev().collect::<Vec<u32>>(), [5, 4, 3, 2, 1]);
+    assert_eq!(view.iter().len(), 5);
+    assert_eq!(view.into_iter().sum::<u32>(), 15);
+
+    let strided = buffer.view_strided::<u16>(1, 3, 8).unwrap();
+    assert_eq!(strided.to_vec(), [1, 3, 5]);
+
+    assert!(buffer.view::<u32>(1, 6).is_err());
+    assert!(buffer.view::<u32>(0x16, 0).is_err());
+    assert!(buffer.view::<u32>(0x15, 0).unwrap().is_empty());
+    assert!(buffer.view_strided::<u32>(0, 2, 3).is_err());
+    assert!(buffer.view::<()>(0, 1).is_err());
+
+    let mut view_mut = buffer.view_mut_strided::<u16>(3, 5, 4).unwrap();
+    assert_eq!(view_mut.get(0), Some(0));
+
+    for i in 0..view_mut.len() {
+        assert!(view_mut.set(i, 0xAAAA).is_ok());
+    }
+
+    assert!(view_mut.set(5, 0).is_err());
+    assert_eq!(view_mut.iter().collect::<Vec<u16>>(), [0xAAAA; 5]);
+    assert_eq!(buffer, hex::decode("ff0100aaaa0200aaaa0300aaaa0400aaaa0500aaaa").unwrap());
+}
+
+#[test]
+fn test_buffer_parse() {
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable, Debug, PartialEq)]
+    struct TableHeader {
+        count: u16,
+        name_offset: u16,
+    }
+
+    #[derive(BufferParse, Debug, PartialEq)]
+    struct Entry {
+        id: u8,
+        #[len_prefix(u8)]
+        name: Vec<u8>,
+    }
+
+    #[derive(BufferParse, Debug, PartialEq)]
+    struct Table {
+        header: TableHeader,
+        #[count = "header.count"]
+        entries: Vec<Entry>,
+        #[align(4)]
+        flags: u32,
+        #[offset = "header.name_offset"]
+        #[len_prefix(u16)]
+        name: Vec<u8>,
+    }
+
+    let table = Table {
+        header: TableHeader { count: 2, name_offset: 0x18 },
+        entries: vec![Entry { id: 1, name: b"ab".to_vec() }, Entry { id: 2, name: b"c".to_vec() }],
+        flags: 0xDEADBEEF,
+        name: b"pk".to_vec(),
+    };
+
+    let mut buffer = VecBuffer::from_data([0xFF; 4]);
+    assert!(table.write_into(&mut buffer).is_ok());
+    assert_eq!(buffer, hex::decode("ffffffff020018000102616202016300efbeadde00000000000000000200706b").unwrap());
+
+    let (parsed, size) = Table::parse(&buffer, 4).unwrap();
+    assert_eq!(size, 0x1C);
+    assert_eq!(parsed, table);
+
+    let (entry, size) = Entry::parse(&buffer, 8).unwrap();
+    assert_eq!(size, 4);
+    assert_eq!(entry.name, b"ab");
+
+    let (header, size) = TableHeader::parse(&buffer, 4).unwrap();
+    assert_eq!(size, 4);
+    assert_eq!(header, table.header);
+
+    assert!(Table::parse(&buffer, 5).is_err());
+    buffer.truncate(buffer.len()-1);
+    assert!(Table::parse(&buffer, 4).is_err());
+
+    let mut bad_count = table;
+    bad_count.header.count = 3;
+    assert!(matches!(bad_count.write_into(&mut VecBuffer::new()), Err(Error::SizeMismatch(3,2))));
+
+    bad_count.header.count = 2;
+    bad_count.header.name_offset = 0x8;
+    assert!(bad_count.write_into(&mut VecBuffer::new()).is_err());
+
+    bad_count.header.name_offset = 0x18;
+    bad_count.name = vec![0u8; 0x10000];
+    assert!(matches!(bad_count.write_into(&mut VecBuffer::new()), Err(Error::Overflow(0x10000))));
+
+    #[derive(BufferParse, Debug, PartialEq)]
+    struct Scalars {
+        count: u16,
+        data_offset: u8,
+        #[count = "count"]
+        values: Vec<u16>,
+        #[offset = "data_offset"]
+        #[count = "count * 2"]
+        data: Vec<u8>,
+    }
+
+    let scalars = Scalars { count: 2, data_offset: 8, values: vec![0xAAAA, 0xBBBB], data: b"pkbf".to_vec() };
+    let mut buffer = VecBuffer::new();
+    assert!(scalars.write_into(&mut buffer).is_ok());
+    assert_eq!(buffer, hex::decode("020008aaaabbbb00706b6266").unwrap());
+    assert_eq!(Scalars::parse(&buffer, 0).unwrap(), (scalars, 12));
+
+    #[derive(BufferParse, Debug)]
+    struct Distant {
+        offset: u64,
+        #[offset = "offset"]
+        value: u8,
+    }
+
+    let mut buffer = VecBuffer::with_initial_size(1);
+    assert!(buffer.append_ref::<u64>(&u64::MAX).is_ok());
+    assert!(matches!(Distant::parse(&buffer, 1), Err(Error::Overflow(1))));
+
+    #[derive(BufferParse, Debug)]
+    struct Signed {
+        count: i8,
+        #[count = "count"]
+        values: Vec<u8>,
+    }
+
+    assert!(matches!(Signed::parse(&VecBuffer::from_data([0xFF, 0x00]), 0), Err(Error::Overflow(_))));
+    assert!(matches!(Signed { count: -1, values: Vec::new() }.write_into(&mut VecBuffer::new()), Err(Error::Overflow(_))));
+}
+
+#[test]
+fn test_castable_layout() {
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable)]
+    struct Section {
+        name: [u8; 4],
+        size: u32,
+        flags: u16,
+        count: u16,
+    }
+
+    #[repr(C, packed)]
+    #[derive(Copy, Clone, Castable)]
+    struct Pair(u8, u32);
+
+    let layout = Section::field_layout();
+    assert_eq!(layout.iter().map(|f| f.name).collect::<Vec<&str>>(), ["name", "size", "flags", "count"]);
+    assert_eq!(layout.iter().map(|f| f.offset).collect::<Vec<usize>>(), [0, 4, 8, 10]);
+    assert_eq!(layout.iter().map(|f| f.size).collect::<Vec<usize>>(), [4, 4, 2, 2]);
+    assert_eq!(layout[0].type_name, "[u8; 4]");
+
+    let layout = Pair::field_layout();
+    assert_eq!(layout[1], FieldLayout { name: "1", offset: 1, size: 4, type_name: "u32", padding: false });
+
+    let buffer = VecBuffer::from_data(hex::decode("002e74657874efbeadde01000200").unwrap());
+    let dump = buffer.dump_struct::<Section>(1).unwrap();
+    let lines = dump.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].ends_with("Section at 0x1 (0xc bytes)"));
+    assert_eq!(lines[1], "  0x1 (+0x0) name: [u8; 4] = 2e 74 65 78");
+    assert_eq!(lines[2], "  0x5 (+0x4) size: u32 = 74 ef be ad");
+    assert_eq!(lines[4], "  0xb (+0xa) count: u16 = 00 02");
+
+    assert!(buffer.dump_struct::<Section>(3).is_err());
+    assert!(buffer.dump_struct::<Pair>(8).is_ok());
+}
+
+#[test]
+fn test_auto_pad() {
+    #[castable(auto_pad)]
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable)]
+    struct Record {
+        kind: u8,
+        value: u64,
+        flags: u16,
+        tag: u8,
+    }
+
+    assert_eq!(std::mem::size_of::<Record>(), 24);
+    assert_eq!(std::mem::size_of_val(&[0u8; 0]), 0);
+
+    let record = Record {
+        kind: 1, _pad_kind: Default::default(),
+        value: 0xFACEBABEDEADBEEF, _pad_value: Default::default(),
+        flags: 0x1234, _pad_flags: Default::default(),
+        tag: 2, _pad_tag: Default::default(),
+    };
+    assert_eq!(record._pad_kind.len(), 7);
+    assert_eq!(record._pad_value.len(), 0);
+    assert_eq!(record._pad_flags.len(), 0);
+    assert_eq!(record._pad_tag.len(), 5);
+
+    let layout = Record::field_layout();
+    assert_eq!(layout.len(), 8);
+    assert_eq!(layout.iter().filter(|f| f.padding).map(|f| f.name).collect::<Vec<&str>>(), ["_pad_kind", "_pad_value", "_pad_flags", "_pad_tag"]);
+    assert_eq!(layout[2].offset, 8);
+    assert_eq!(layout[6].offset, 18);
+
+    let mut buffer = VecBuffer::with_initial_size(32);
+    assert!(buffer.write_ref::<Record>(8, &record).is_ok());
+    assert_eq!(buffer.get_ref_checked::<Record>(8).unwrap().value, 0xFACEBABEDEADBEEF);
+
+    buffer[8+20] = 0xFF;
+    assert!(buffer.get_ref::<Record>(8).is_ok());
+    assert_eq!(buffer.get_ref_checked::<Record>(8).err().unwrap().root(), &Error::NonZeroPadding(28));
+
+    #[castable(auto_pad)]
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable)]
+    struct Keyword {
+        r#type: u8,
+        r#match: u32,
+    }
+
+    let keyword = Keyword { r#type: 1, _pad_type: Default::default(), r#match: 2, _pad_match: Default::default() };
+    assert_eq!(keyword._pad_type.len(), 3);
+    assert_eq!(keyword._pad_match.len(), 0);
+    assert_eq!(Keyword::field_layout().iter().map(|f| f.name).collect::<Vec<&str>>(), ["type", "_pad_type", "match", "_pad_match"]);
+}
+
+#[test]
+fn test_castable_union() {
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable)]
+    union ThunkData {
+        forwarder_string: u64,
+        function: u64,
+        ordinal: u64,
+        address_of_data: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Copy, Clone, Castable)]
+    union Register {
+        value: u32,
+        bytes: [u8; 4],
+        words: [u16; 2],
+    }
+
+    #[repr(transparent)]
+    #[derive(Copy, Clone, Castable)]
+    struct Tagged<T, Tag>(T, std::marker::PhantomData<Tag>);
+
+    let buffer = VecBuffer::from_data(hex::decode("efbeaddebebacefa").unwrap());
+
+    let thunk = buffer.get_ref::<ThunkData>(0).unwrap();
+    assert_eq!(unsafe { thunk.ordinal }, 0xFACEBABEDEADBEEF);
+    assert!(ThunkData::field_layout().iter().all(|f| f.offset == 0 && f.size == 8));
+
+    let register = buffer.read_val::<Register>(4).unwrap();
+    assert_eq!(unsafe { register.value }, 0xFACEBABE);
+    assert_eq!(unsafe { register.bytes }, [0xBE, 0xBA, 0xCE, 0xFA]);
+    assert_eq!(unsafe { register.words }, [0xBABE, 0xFACE]);
+
+    let tagged = buffer.get_ref::<Tagged<u32, u8>>(4).unwrap();
+    assert_eq!(tagged.0, 0xFACEBABE);
+    assert_eq!(buffer.read_val::<Tagged<[u16; 2], ()>>(0).unwrap().0, [0xBEEF, 0xDEAD]);
+
+    let layout = Tagged::<u64, u8>::field_layout();
+    assert_eq!(layout[0].size, 8);
+    assert_eq!(layout[1].size, 0);
+}
+
+#[test]
+fn test_castable_repr() {
+    #[repr(C, packed(2))]
+    #[derive(Copy, Clone, Castable)]
+    struct Packed {
+        kind: u16,
+        value: u32,
+    }
+
+    #[repr(C)]
+    #[repr(align(16))]
+    #[derive(Copy, Clone, Castable)]
+    struct Aligned {
+        x: u32,
+        y: u32,
+        z: u32,
+        w: u32,
+    }
+
+    #[castable(auto_pad)]
+    #[repr(C, packed(2))]
+    #[derive(Copy, Clone, Castable)]
+    struct PackedPad {
+        kind: u8,
+        value: u32,
+    }
+
+    #[castable(auto_pad)]
+    #[repr(C, align(16))]
+    #[derive(Copy, Clone, Castable)]
+    struct AlignedPad {
+        kind: u8,
+        value: u32,
+    }
+
+    assert_eq!(std::mem::size_of::<Packed>(), 6);
+    assert_eq!(std::mem::align_of::<Packed>(), 2);
+    assert_eq!(std::mem::align_of::<Aligned>(), 16);
+
+    assert_eq!(std::mem::size_of::<PackedPad>(), 6);
+    assert_eq!(PackedPad::field_layout()[1].size, 1);
+    assert_eq!(PackedPad::field_layout()[3].size, 0);
+
+    assert_eq!(std::mem::size_of::<AlignedPad>(), 16);
+    assert_eq!(AlignedPad::field_layout()[1].size, 3);
+    assert_eq!(AlignedPad::field_layout()[3].size, 8);
+
+    let buffer = VecBuffer::from_data(hex::decode("0100efbeaddebebacefa0000000000000000").unwrap());
+    let packed = buffer.get_ref::<Packed>(0).unwrap();
+    assert_eq!({ packed.value }, 0xDEADBEEF);
+    assert_eq!(buffer.read_val::<Aligned>(2).unwrap().y, 0xFACEBABE);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    use pkbuffer::serialize::{ByteImage, SerializeBuffer};
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Fixture {
+        raw: VecBuffer,
+        #[serde(with = "pkbuffer::serialize::hex")]
+        hex: VecBuffer,
+        #[serde(with = "pkbuffer::serialize::base64")]
+        base64: VecBuffer,
+    }
+
+    let data = hex::decode("deadbeefabad1dea").unwrap();
+    let fixture = Fixture {
+        raw: VecBuffer::from_data(&data[..3]),
+        hex: VecBuffer::from_data(&data),
+        base64: VecBuffer::from_data(&data[..5]),
+    };
+
+    let json = serde_json::to_string(&fixture).unwrap();
+    assert_eq!(json, r#"{"raw":[222,173,190],"hex":"deadbeefabad1dea","base64":"3q2+76s="}"#);
+
+    let parsed: Fixture = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.raw, data[..3]);
+    assert_eq!(parsed.hex, data);
+    assert_eq!(parsed.base64, data[..5]);
+    assert_eq!(serde_json::from_str::<Fixture>(r#"{"raw":[],"hex":"DEADBEEFABAD1DEA","base64":""}"#).unwrap().hex, data);
+    assert!(serde_json::from_str::<Fixture>(r#"{"raw":[],"hex":"abc","base64":""}"#).is_err());
+    assert!(serde_json::from_str::<Fixture>(r#"{"raw":[],"hex":"","base64":"3q2+7"}"#).is_err());
+
+    for len in 0..data.len() {
+        let encoded = pkbuffer::serialize::base64::encode(&data[..len]);
+        assert_eq!(pkbuffer::serialize::base64::decode(&encoded).unwrap(), data[..len]);
+        assert_eq!(pkbuffer::serialize::base64::decode(encoded.trim_end_matches('=')).unwrap(), data[..len]);
+    }
+
+    let ptr = PtrBuffer::new(data.as_ptr(), 2);
+    assert_eq!(serde_json::to_string(&ptr).unwrap(), "[222,173]");
+    assert_eq!(serde_json::to_string(&SerializeBuffer(&ptr)).unwrap(), "[222,173]");
+
+    let image = ByteImage(0xFACEBABEu32);
+    assert_eq!(serde_json::to_string(&image).unwrap(), "[190,186,206,250]");
+    assert_eq!(serde_json::from_str::<ByteImage<u32>>("[190,186,206,250]").unwrap().0, 0xFACEBABE);
+    assert!(serde_json::from_str::<ByteImage<u32>>("[190,186,206]").is_err());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes() {
+    let mut wrapped = SliceBuffer::new(bytes::BytesMut::from(&hex::decode("deadbeefabad1dea").unwrap()[..]));
+    assert_eq!(wrapped.read_val::<u32>(4).unwrap(), 0xEA1DADAB);
+    assert!(wrapped.write_val::<u16>(0, 0xFACE).is_ok());
+    assert_eq!(wrapped.search([0xEF, 0xAB]).unwrap().collect::<Vec<usize>>(), [3]);
+
+    // slice methods aren't shadowed by Buffer methods
+    let bytes = wrapped.into_inner();
+    assert!(bytes.contains(&0xFA));
+    assert!(bytes::Bytes::from_static(b"abc").contains(&b'a'));
+
+    let buffer = VecBuffer::from(bytes.clone());
+    assert_eq!(buffer, hex::decode("cefabeefabad1dea").unwrap());
+    assert_eq!(bytes::BytesMut::from(buffer.clone()), bytes);
+    assert_eq!(bytes::Bytes::from(buffer.clone()), bytes.freeze());
+    assert_eq!(VecBuffer::from(bytes::Bytes::from_static(b"pk")), *b"pk");
+
+    let frozen = SliceBuffer::new(bytes::Bytes::from_static(b"\x50\x4b\x03\x04"));
+    assert_eq!(frozen.read_val::<u32>(0).unwrap(), 0x04034B50);
+    assert_eq!(frozen.search(b"\x03").unwrap().collect::<Vec<usize>>(), [2]);
+}
+
+#[cfg(all(feature = "bytemuck", feature = "zerocopy"))]
+#[test]
+fn test_pod_bridges() {
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pair {
+        first: u16,
+        second: u16,
+    }
+    unsafe impl bytemuck::Zeroable for Pair {}
+    unsafe impl bytemuck::Pod for Pair {}
+
+    let mut buffer = VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap());
+    let pair = buffer.get_ref::<BytemuckCastable<Pair>>(0).unwrap();
+    assert_eq!(pair.second, 0xEFBE);
+
+    let pairs = buffer.view::<BytemuckCastable<Pair>>(0, 2).unwrap().to_vec();
+    assert_eq!(pairs[1].into_inner(), Pair { first: 0xADAB, second: 0xEA1D });
+
+    assert!(buffer.write_ref(4, BytemuckCastable::from_ref(&Pair { first: 1, second: 2 })).is_ok());
+    assert_eq!(buffer.get_ref::<ZerocopyCastable<[u16; 2]>>(4).unwrap().0, [1, 2]);
+
+    let words = buffer.get_mut_ref::<ZerocopyCastable<[u16; 4]>>(0).unwrap();
+    words[0] = 0xFACE;
+    assert_eq!(buffer[..2], [0xCE, 0xFA]);
+}
+
+#[test]
+fn test_containers() {
+    let data = hex::decode("deadbeefabad1dea").unwrap();
+
+    // the slice methods of std containers aren't shadowed by Buffer methods
+    let mut vec = data.clone();
+    assert!(vec.contains(&0xAD));
+    assert_eq!(vec.iter().rev().position(|&byte| byte == 0x1D), Some(1));
+    let (left, _) = vec.split_at_mut(2);
+    left.fill(0);
+    assert_eq!([1u8, 2, 3].iter().rev().copied().collect::<Vec<u8>>(), [3, 2, 1]);
+
+    let mut vec = SliceBuffer::new(data.clone());
+    assert_eq!(*vec.get_ref::<u32>(4).unwrap(), 0xEA1DADAB);
+    assert!(vec.write_val::<u16>(0, 0xFACE).is_ok());
+    assert_eq!(vec[..2], [0xCE, 0xFA]);
+
+    let mut boxed = SliceBuffer::from(data.clone().into_boxed_slice());
+    assert!(boxed.write_ref::<u8>(7, &0xFF).is_ok());
+    assert_eq!(boxed.to_vec(), hex::decode("deadbeefabad1dff").unwrap());
+
+    let mut array = SliceBuffer::new([0u8; 8]);
+    assert!(array.write(0, &data).is_ok());
+    assert_eq!(array.read_val::<u64>(0).unwrap(), u64::from_le_bytes(array.into_inner()));
+    assert_eq!(VecBuffer::from_data(array.inner()), array);
+
+    let mut storage = data.clone();
+    let mut slice = SliceBuffer::new(&mut storage[2..6]);
+    assert_eq!(slice.len(), 4);
+    assert!(slice.write_val::<u32>(0, 0).is_ok());
+    assert_eq!(storage, hex::decode("dead000000001dea").unwrap());
+
+    let mut cow = std::borrow::Cow::Borrowed(&data[..]);
+    assert_eq!(SliceBuffer::new(&cow).read_val::<u16>(2).unwrap(), 0xEFBE);
+    assert!(matches!(cow, std::borrow::Cow::Borrowed(_)));
+    assert!(SliceBuffer::new(cow.to_mut()).write_val::<u8>(0, 0).is_ok());
+    assert!(matches!(cow, std::borrow::Cow::Owned(_)));
+    assert_eq!(cow[0], 0);
+    assert_eq!(data[0], 0xDE);
+
+    let shared: std::sync::Arc<[u8]> = data.clone().into();
+    assert_eq!(SliceBuffer::new(shared).read_val::<u8>(7).unwrap(), 0xEA);
+}
+
+#[test]
+fn test_buffer_mut() {
+    let mut data = hex::decode("deadbeefabad1dea").unwrap();
+
+    let mut buffer = PtrBufferMut::new(data.as_mut_ptr(), data.len());
+    assert!(buffer.write_val::<u32>(0, 0xFACEBABE).is_ok());
+    assert_eq!(*buffer.get_ref::<u32>(0).unwrap(), 0xFACEBABE);
+
+    let mut sub = buffer.sub_buffer(2, 2).unwrap();
+    assert_eq!(sub.offset(), 2);
+    sub.write_val::<u16>(0, 0xBEEF).unwrap();
+    assert_eq!(buffer.read_val::<u32>(0).unwrap(), 0xBEEFBABE);
+    assert!(buffer.sub_buffer(4, 5).is_err());
+    assert_eq!(buffer.sub_buffer(4, usize::MAX).err(), Some(Error::OutOfBounds(8,usize::MAX)));
+    assert_eq!(buffer.as_ptr_buffer().sub_buffer(1, usize::MAX).err(), Some(Error::OutOfBounds(8,usize::MAX)));
+    buffer.write_val::<u32>(0, 0xFACEBABE).unwrap();
+
+    let (mut left, right) = buffer.split_at(4).unwrap();
+    left.fill(0xFF);
+    assert_eq!(right, hex::decode("abad1dea").unwrap());
+    assert_eq!(PtrBuffer::from(left), [0xFF; 4]);
+    assert_eq!(data, hex::decode("ffffffffabad1dea").unwrap());
+
+    let shared = SliceBuffer::new(std::sync::Arc::<[u8]>::from(data.clone()));
+    assert_eq!(shared.read_val::<u16>(4).unwrap(), 0xADAB);
+    assert_eq!(shared.search([0x1D]).unwrap().collect::<Vec<usize>>(), [6]);
+
+    let slice = &data[4..];
+    assert_eq!(Buffer::len(&slice), 4);
+    assert_eq!(slice.read_val::<u32>(0).unwrap(), 0xEA1DADAB);
+}
+
+#[test]
+fn test_error_context() {
+    let buffer = VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap());
+
+    let error = buffer.get_ref::<u64>(4).unwrap_err();
+    assert_eq!(error.root(), &Error::OutOfBounds(8, 12));
+    assert_eq!(error, Error::Context(Box::new(Error::OutOfBounds(8, 12)), ErrorContext::Operation {
+        name: "get_ref",
+        type_name: Some("u64"),
+        offset: Some(4),
+        length: 8,
+    }));
+
+    let error = buffer.search([0u8; 9]).context("looking for the header").err().unwrap();
+    assert_eq!(error.root(), &Error::OutOfBounds(8, 9));
+    assert_eq!(error.to_string(), "looking for the header: search (0x9 bytes): out of bounds: boundary is 0x8, got 0x9 instead");
+
+    let mut buffer = buffer;
+    let error = buffer.write_val::<u32>(6, 0)
+        .with_context(|| format!("patching entry {}", 3))
+        .context("applying patches")
+        .unwrap_err();
+    assert_eq!(error.to_string(), "applying patches: patching entry 3: write_val::<u32> at 0x6 (0x4 bytes): out of bounds: boundary is 0x8, got 0xa instead");
+
+    let error = buffer.checksum(4..10, Algorithm::Crc32).unwrap_err();
+    assert_eq!(error.to_string(), "checksum at 0x4 (0x6 bytes): out of bounds: boundary is 0x8, got 0xa instead");
+    assert_eq!(buffer.entropy(..9).unwrap_err().to_string(), "entropy at 0x0 (0x9 bytes): out of bounds: boundary is 0x8, got 0x9 instead");
+    assert_eq!(buffer.end_with([0u8; 9]).unwrap_err().to_string(), "end_with (0x9 bytes): out of bounds: boundary is 0x8, got 0x9 instead");
+
+    let other = VecBuffer::from_data([0u8; 4]);
+    let error = buffer.make_mut_ref(other.get_ref::<u32>(0).unwrap()).unwrap_err();
+    assert_eq!(error, Error::Context(Box::new(Error::InvalidPointer(other.as_ptr() as usize)), ErrorContext::Operation {
+        name: "make_mut_ref",
+        type_name: Some("u32"),
+        offset: None,
+        length: 4,
+    }));
+
+    let io_error = Error::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+    assert_eq!(io_error, Error::from(std::io::Error::from(std::io::ErrorKind::NotFound)));
+    assert_ne!(io_error, Error::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied)));
+    assert!(std::error::Error::source(&io_error.context("loading")).is_some());
+}
+
+#[test]
+fn test_error_kind() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Error>();
+
+    let buffer = VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap());
+    let other = VecBuffer::from_data([0u8; 4]);
+
+    let error = buffer.ref_to_offset(other.get_ref::<u32>(0).unwrap()).unwrap_err();
+    assert_eq!(error, Error::InvalidPointer(other.as_ptr() as usize));
+    assert_eq!(error.kind(), ErrorKind::InvalidPointer);
+
+    let error = buffer.read_val::<u32>(6).context("reading the trailer").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::OutOfBounds);
+    assert_eq!(Error::from(std::io::Error::from(std::io::ErrorKind::NotFound)).kind(), ErrorKind::IoError);
+}
+
+#[test]
+fn test_buffer_builder() {
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Castable)]
+    struct Header {
+        magic: u32,
+        table_offset: u32,
+        table_size: u32,
+    }
+
+    let mut builder = BufferBuilder::new();
+    let header = builder.alloc::<Header>().unwrap();
+    let table_size = builder.reserve_patch::<u32>().unwrap();
+    assert_eq!(table_size.offset(), 0xC);
+
+    let flag = builder.alloc_val::<u8>(0xFF).unwrap();
+    let table = builder.alloc_slice::<u64>(2).unwrap();
+    assert_eq!(flag.offset(), 0x10);
+    assert_eq!(table.offset(), 0x18);
+    assert_eq!(table.get(1).unwrap().offset(), 0x20);
+    assert!(table.get(2).is_none());
+    assert!(builder.write_slice(table, &[1]).is_err());
+    assert!(builder.write_slice(table, &[0xDEADBEEF, 0xABAD1DEA]).is_ok());
+
+    let name = builder.alloc_str("kernel32.dll");
+    let other = builder.alloc_str("user32.dll");
+    assert_eq!(builder.alloc_str("kernel32.dll"), name);
+    assert_eq!(other.offset(), name.offset() + 13);
+    assert_eq!(builder.align(8), 0x40);
+
+    assert!(builder.write(header, Header { magic: 0x4B50, table_offset: table.offset() as u32, table_size: 0 }).is_ok());
+    assert_eq!(builder.read(header).unwrap().table_offset, 0x18);
+    assert!(builder.clone().finish().is_err());
+    assert!(builder.patch(table_size, table.size() as u32).is_ok());
+
+    let buffer = builder.finish().unwrap();
+    assert_eq!(buffer.len(), 0x40);
+    assert_eq!(buffer.read_val::<Header>(0).unwrap(), Header { magic: 0x4B50, table_offset: 0x18, table_size: 0 });
+    assert_eq!(buffer.read_val::<u32>(0xC).unwrap(), 0x10);
+    assert_eq!(buffer.read_vals::<u64>(0x18, 2).unwrap(), [0xDEADBEEF, 0xABAD1DEA]);
+    assert_eq!(buffer.read(name.offset(), name.len()).unwrap(), b"kernel32.dll");
+
+    let mut builder = BufferBuilder::new();
+    let _ = builder.reserve_patch::<u16>().unwrap();
+    assert_eq!(builder.alloc_slice::<u64>(usize::MAX).unwrap_err(), Error::Overflow(usize::MAX));
+    assert_eq!(builder.alloc_bytes(usize::MAX, 1).unwrap_err(), Error::Overflow(2));
+    assert_eq!(builder.len(), 2);
+    assert_eq!(builder.finish().unwrap_err(), Error::UnpatchedReservation(0));
+}
+
+#[test]
+fn test_builder_fixups() {
+    let mut builder = BufferBuilder::new();
+    let section = builder.forward_label();
+    let entry = builder.forward_label();
+    let start = builder.label();
+    assert_eq!(builder.label_offset(start), Some(0));
+    assert_eq!(builder.label_offset(section), None);
+
+    let section_offset = builder.alloc::<u64>().unwrap();
+    let entry_rva = builder.alloc::<u32>().unwrap();
+    let back_reference = builder.alloc::<u32>().unwrap();
+    builder.fixup_u64(section_offset.offset(), section, FixupKind::Absolute);
+    builder.fixup_u32(entry_rva.offset(), entry, FixupKind::BaseRelative(0x10));
+    builder.fixup_u32(back_reference.offset(), start, FixupKind::Relative);
+
+    assert_eq!(builder.resolve(), Err(Error::UnresolvedLabel(section.id())));
+    assert_eq!(builder.as_buffer().read_val::<u64>(0).unwrap(), 0);
+
+    builder.bind(section);
+    builder.alloc_bytes(0x8, 1).unwrap();
+    assert_eq!(builder.bind(entry), 0x18);
+    builder.alloc_val::<u8>(0xC3).unwrap();
+
+    assert!(builder.resolve().is_ok());
+    let buffer = builder.finish().unwrap();
+    assert_eq!(buffer.read_val::<u64>(0).unwrap(), 0x10);
+    assert_eq!(buffer.read_val::<u32>(8).unwrap(), 0x8);
+    assert_eq!(buffer.read_val::<i32>(12).unwrap(), -12);
+
+    let mut builder = BufferBuilder::new();
+    let early = builder.label();
+    let offset = builder.alloc::<u32>().unwrap();
+    builder.fixup_u32(offset.offset(), early, FixupKind::BaseRelative(4));
+    assert_eq!(builder.resolve(), Err(Error::Overflow(0)));
+
+    let mut builder = BufferBuilder::new();
+    let end = builder.forward_label();
+    builder.fixup_u32(0, end, FixupKind::Absolute);
+    builder.bind(end);
+    assert!(builder.finish().unwrap_err().kind() == ErrorKind::OutOfBounds);
+
+    let mut builder = BufferBuilder::new();
+    let start = builder.label();
+    builder.fixup_u64(usize::MAX, start, FixupKind::Absolute);
+    assert_eq!(builder.resolve(), Err(Error::Overflow(usize::MAX)));
+}
+
+#[test]
+fn test_splice() {
+    let mut buffer = VecBuffer::from_data(hex::decode("00112233445566778899").unwrap());
+    let before = buffer.anchor(1);
+    let inside = buffer.anchor(4);
+    let after = buffer.anchor(8);
+    let end = buffer.anchor(10);
+
+    let shift = buffer.splice(3..6, [0xAA]).unwrap();
+    assert_eq!(shift, OffsetShift { start: 3, removed: 3, inserted: 1 });
+    assert_eq!(shift.delta(), -2);
+    assert_eq!(buffer, hex::decode("001122aa66778899").unwrap());
+    assert_eq!(buffer.anchor_offset(before), Some(1));
+    assert_eq!(buffer.anchor_offset(inside), None);
+    assert_eq!(buffer.anchor_offset(after), Some(6));
+    assert_eq!(buffer.anchor_offset(end), Some(8));
+    assert_eq!(buffer[buffer.anchor_offset(after).unwrap()], 0x88);
+
+    let shift = buffer.insert_bytes(1, [0xBB, 0xCC]).unwrap();
+    assert_eq!(shift.apply(0), Some(0));
+    assert_eq!(shift.apply(1), Some(3));
+    assert_eq!(buffer.anchor_offset(before), Some(3));
+    assert_eq!(buffer[3], 0x11);
+
+    buffer.remove(0);
+    buffer.insert(buffer.len(), 0xDD);
+    assert_eq!(buffer.anchor_offset(after), Some(7));
+    assert_eq!(buffer.anchor_offset(end), Some(10));
+    assert_eq!(buffer, hex::decode("bbcc1122aa66778899dd").unwrap());
+
+    buffer.remove_anchor(after);
+    assert_eq!(buffer.anchor_offset(after), None);
+    let replacement = buffer.anchor(2);
+    assert_ne!(replacement, after);
+    assert_ne!(replacement, inside);
+    assert_eq!(buffer.anchor_offset(after), None);
+    assert_eq!(buffer.anchor_offset(inside), None);
+    assert_eq!(buffer.anchor_offset(replacement), Some(2));
+    assert!(buffer.splice(..=usize::MAX, []).is_err());
+    assert!(buffer.splice(4..11, []).is_err());
+    assert!(buffer.insert_bytes(11, [0]).is_err());
+    assert_eq!(buffer.splice(.., []).unwrap().removed, 10);
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_transactional_buffer() {
+    let mut buffer = TransactionalBuffer::with_limit(VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap()), 3);
+
+    buffer.begin();
+    assert!(buffer.write_val::<u32>(0, 0).is_ok());
+    buffer.begin();
+    *buffer.get_mut_ref::<u8>(4).unwrap() = 0xFF;
+    buffer.fill(0x11);
+    assert_eq!(buffer.depth(), 2);
+    assert!(buffer.rollback());
+    assert_eq!(buffer.as_slice(), hex::decode("00000000abad1dea").unwrap());
+    assert!(!buffer.undo());
+    assert!(buffer.commit());
+    assert!(!buffer.commit());
+    assert!(!buffer.rollback());
+
+    assert!(buffer.write(6, [0x22, 0x33]).is_ok());
+    assert!(buffer.write(8, [0x44]).is_err());
+    buffer.swap(0, 7);
+    assert_eq!(buffer.as_slice(), hex::decode("33000000abad2200").unwrap());
+
+    assert_eq!(buffer.to_patch(), [
+        BytePatch { offset: 0, old: vec![0xDE, 0xAD, 0xBE, 0xEF], new: vec![0x33, 0, 0, 0] },
+        BytePatch { offset: 6, old: vec![0x1D, 0xEA], new: vec![0x22, 0x00] },
+    ]);
+
+    assert!(buffer.undo());
+    assert!(buffer.undo());
+    assert_eq!(buffer.as_slice(), hex::decode("00000000abad1dea").unwrap());
+    assert!(buffer.redo());
+    assert_eq!(buffer.as_slice(), hex::decode("00000000abad2233").unwrap());
+    assert!(buffer.can_redo());
+
+    // failed calls leave the history alone
+    let misaligned = if buffer.as_ptr() as usize % 2 == 0 { 1 } else { 0 };
+    assert_eq!(buffer.get_mut_slice_ref::<u16>(misaligned, 1).unwrap_err().kind(), ErrorKind::BadAlignment);
+    assert!(buffer.write(7, [0x44, 0x55]).is_err());
+    assert!(buffer.can_redo());
+
+    // a new change forgets the redo history, and the limit of 3 steps forgets the transaction
+    assert!(buffer.write_val::<u8>(0, 0x01).is_ok());
+    assert!(!buffer.can_redo());
+    assert!(buffer.write_val::<u8>(1, 0x02).is_ok());
+    assert_eq!(buffer.to_patch(), [
+        BytePatch { offset: 0, old: vec![0, 0], new: vec![1, 2] },
+        BytePatch { offset: 6, old: vec![0x1D, 0xEA], new: vec![0x22, 0x33] },
+    ]);
+
+    let mut original = VecBuffer::from_data(hex::decode("00000000abad1dea").unwrap());
+    buffer.to_patch().iter().for_each(|patch| patch.apply(&mut original).unwrap());
+    assert_eq!(original, buffer.as_slice());
+    buffer.to_patch().iter().for_each(|patch| patch.revert(&mut original).unwrap());
+    assert_eq!(original, hex::decode("00000000abad1dea").unwrap());
+
+    assert!(buffer.undo());
+    assert!(buffer.undo());
+    assert!(buffer.undo());
+    assert!(!buffer.undo());
+    assert_eq!(buffer.into_inner(), hex::decode("00000000abad1dea").unwrap());
+}
+
+#[test]
+fn test_shared_buffer() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedBuffer>();
+    assert_send_sync::<RegionGuardMut<'static>>();
+
+    let shared = SharedBuffer::from(VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap()));
+    assert_eq!(shared.len(), 8);
+
+    {
+        let header = shared.region(..4).unwrap();
+        let overlapping = shared.region(2..6).unwrap();
+        assert_eq!(header.read_val::<u32>(0).unwrap(), 0xEFBEADDE);
+        assert_eq!(overlapping.offset(), 2);
+        assert_eq!(overlapping.as_slice(), hex::decode("beefabad").unwrap());
+
+        assert_eq!(shared.region_mut(3..5).unwrap_err(), Error::RegionBorrowed(3, 5));
+        assert!(shared.region_mut(6..).is_ok());
+        assert!(shared.region_mut(4..4).is_ok());
+        assert!(shared.region(..9).is_err());
+        assert_eq!(shared.region(..=usize::MAX).unwrap_err(), Error::Overflow(usize::MAX));
+        assert_eq!(shared.region((std::ops::Bound::Excluded(usize::MAX), std::ops::Bound::Unbounded)).unwrap_err(), Error::Overflow(usize::MAX));
+    }
+
+    std::thread::scope(|scope| {
+        for index in 0..4 {
+            let mut region = shared.region_mut(index*2..index*2+2).unwrap();
+            assert_eq!(shared.region(index*2+1..=index*2+1).unwrap_err().kind(), ErrorKind::RegionBorrowed);
+
+            scope.spawn(move || {
+                let value = region.read_val::<u16>(0).unwrap();
+                region.write_val::<u16>(0, value.swap_bytes()).unwrap();
+            });
+        }
+    });
+
+    let mut region = shared.region_mut(..).unwrap();
+    region.reverse();
+    drop(region);
+
+    let mut shared = shared;
+    shared.get_mut()[0] = 0;
+    assert_eq!(shared.into_vec_buffer(), hex::decode("00eaabadbeefdead").unwrap());
+}
+
+#[test]
+fn test_split_mut() {
+    let mut buffer = VecBuffer::from_data(hex::decode("deadbeefabad1dea").unwrap());
+
+    {
+        let (mut left, mut right) = buffer.split_regions_mut(4).unwrap();
+        assert_eq!(left, hex::decode("deadbeef").unwrap());
+        assert_eq!(right.offset(), 4);
+        left.swap_with_data(right.as_mut_slice());
+    }
+    assert_eq!(buffer, hex::decode("abad1deadeadbeef").unwrap());
+    assert_eq!(buffer.split_regions_mut(9).err().as_ref().map(Error::root), Some(&Error::OutOfBounds(8,9)));
+
+    let (empty, whole) = buffer.split_regions_mut(0).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(whole.len(), 8);
+
+    let mut regions = buffer.split_many_mut(&[6..8, 0..2, 4..4, 2..6]).unwrap();
+    assert_eq!(regions.iter().map(SubBufferMut::offset).collect::<Vec<usize>>(), [6, 0, 4, 2]);
+    assert!(regions[2].is_empty());
+
+    std::thread::scope(|scope| {
+        for region in regions.iter_mut() {
+            scope.spawn(move || region.reverse());
+        }
+    });
+    drop(regions);
+    assert_eq!(buffer, hex::decode("adabaddeea1defbe").unwrap());
+
+    let mut nested = buffer.split_many_mut(&[4..4, 0..4]).unwrap().pop().unwrap();
+    let (_, mut tail) = nested.split_regions_mut(2).unwrap();
+    tail.write_val::<u16>(0, 0).unwrap();
+    assert_eq!(nested.into_mut_slice(), [0xAD, 0xAB, 0x00, 0x00]);
+
+    assert_eq!(buffer.split_many_mut(&[0..4, 6..8, 3..5]).err().as_ref().map(Error::root), Some(&Error::OverlappingRanges(3,5)));
+    assert_eq!(buffer.split_many_mut(&[3..5, 0..4]).err().as_ref().map(Error::root), Some(&Error::OverlappingRanges(0,4)));
+    assert_eq!(buffer.split_many_mut(&[0..2, 2..9]).err().as_ref().map(Error::root), Some(&Error::OutOfBounds(8,9)));
+    assert!(buffer.split_many_mut(&[0..4, 4..8, 2..2]).is_ok());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_search() {
+    // a small alphabet makes for plenty of matches on every chunk boundary
+    let mut state = 0x2545F491u32;
+    let data = (0..0x100003).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        b"ab"[(state & 1) as usize]
+    }).collect::<Vec<u8>>();
+    let buffer = VecBuffer::from_data(&data);
+
+    let expected = (0..=data.len()-5).filter(|&i| &data[i..i+5] == b"abbab").collect::<Vec<usize>>();
+    assert!(expected.len() > 1000);
+    assert_eq!(buffer.par_search(b"abbab").unwrap(), expected);
+
+    let dynamic = [Some(b'a'), None, Some(b'b'), None, Some(b'b')];
+    let dynamic_expected = buffer.search_dynamic(dynamic).unwrap().collect::<Vec<usize>>();
+    assert_eq!(buffer.par_search_dynamic(dynamic).unwrap(), dynamic_expected);
+    assert_eq!(buffer.par_search_dynamic([None, Some(b'a')]).unwrap(), (0..data.len()-1).filter(|&i| data[i+1] == b'a').collect::<Vec<usize>>());
+
+    let overlapping = VecBuffer::from_data([0u8; 0x30001]);
+    assert_eq!(overlapping.par_search([0, 0, 0]).unwrap(), (0..0x2FFFF).collect::<Vec<usize>>());
+    assert_eq!(overlapping.par_search([]).unwrap().len(), 0x30002);
+
+    let terms: [&[u8]; 3] = [b"abbab", b"bb", b"abbab"];
+    let many = buffer.par_search_many(&terms).unwrap();
+    let mut many_expected = Vec::<(usize, usize)>::new();
+
+    for offset in 0..data.len() {
+        for (index, term) in terms.iter().enumerate() {
+            if data[offset..].starts_with(term) { many_expected.push((offset, index)); }
+        }
+    }
+
+    assert_eq!(many, many_expected);
+    assert!(buffer.par_search_many::<&[u8]>(&[]).unwrap().is_empty());
+
+    let small = VecBuffer::from_data([1u8, 2, 3]);
+    assert_eq!(small.par_search([1, 2, 3, 4]).err().as_ref().map(Error::root), Some(&Error::OutOfBounds(3,4)));
+    assert_eq!(small.par_search_dynamic([None, None]).err().as_ref().map(Error::root), Some(&Error::SearchMatchesEverything));
+    assert_eq!(small.par_search_many(&[&[1u8][..], &[0u8; 4][..]]).err().as_ref().map(Error::root), Some(&Error::OutOfBounds(3,4)));
+}
+
+#[test]
+fn test_stream_searcher() {
+    /// A reader which returns at most three bytes at a time, then fails if asked to.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        fail: bool,
+    }
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.data.is_empty() && self.fail {
+                return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "pipe closed"));
+            }
+
+            let size = std::cmp::min(3, std::cmp::min(buf.len(), self.data.len()));
+            buf[..size].copy_from_slice(&self.data[..size]);
+            self.data = &self.data[size..];
+            Ok(size)
+        }
+    }
+
+    let data = hex::decode("deadbeefdeaddeaddeadbeefabad1deadeadbeef").unwrap();
+    let buffer = VecBuffer::from_data(&data);
+    let dynamic = [Some(0xDE), Some(0xAD), None, Some(0xEF)];
+    let expected_dynamic = buffer.search_dynamic(dynamic).unwrap().map(|offset| offset as u64).collect::<Vec<u64>>();
+    assert_eq!(expected_dynamic, [0, 8, 16]);
+
+    for chunk_size in [0, 1, 2, 3, 5, 64, StreamSearcher::<&[u8]>::DEFAULT_CHUNK_SIZE] {
+        let mut searcher = StreamSearcher::new(&data[..], [0xDE, 0xAD]).unwrap();
+        searcher.set_chunk_size(chunk_size);
+        assert_eq!(searcher.collect::<Result<Vec<u64>, Error>>().unwrap(), [0, 4, 6, 8, 16]);
+
+        let mut searcher = StreamSearcher::new_dynamic(Trickle { data: &data, fail: false }, dynamic).unwrap();
+        searcher.set_chunk_size(chunk_size);
+        assert_eq!(searcher.collect::<Result<Vec<u64>, Error>>().unwrap(), expected_dynamic);
+    }
+
+    let repeated = [0u8; 10];
+    let mut searcher = StreamSearcher::new(&repeated[..], [0, 0, 0]).unwrap();
+    searcher.set_chunk_size(4);
+    assert_eq!(searcher.collect::<Result<Vec<u64>, Error>>().unwrap(), (0..8).collect::<Vec<u64>>());
+
+    let mut searcher = StreamSearcher::new(Trickle { data: &data, fail: true }, [0xAB, 0xAD]).unwrap();
+    assert_eq!(searcher.next().unwrap().unwrap(), 12);
+    assert_eq!(searcher.next().unwrap().err().map(|err| err.kind()), Some(ErrorKind::IoError));
+    assert!(searcher.next().is_none());
+
+    assert!(StreamSearcher::new(&data[..], [0u8; 32]).unwrap().next().is_none());
+    assert_eq!(StreamSearcher::new(&data[..], []).err(), Some(Error::SearchMatchesEverything));
+    assert_eq!(StreamSearcher::new_dynamic(&data[..], [None, None]).err(), Some(Error::SearchMatchesEverything));
+}
@@ -0,0 +1,274 @@
+use crate::{Buffer, BufferMut, Castable, Error};
+use std::collections::{BTreeMap, VecDeque};
+
+/// A contiguous run of changed bytes, as exported by [`TransactionalBuffer::to_patch`](TransactionalBuffer::to_patch).
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct BytePatch {
+    /// The offset of the changed bytes.
+    pub offset: usize,
+    /// The bytes before the change.
+    pub old: Vec<u8>,
+    /// The bytes after the change.
+    pub new: Vec<u8>,
+}
+impl BytePatch {
+    /// Write the new bytes of this patch to the given *buffer*.
+    pub fn apply<B: BufferMut + ?Sized>(&self, buffer: &mut B) -> Result<(), Error> {
+        buffer.write(self.offset, &self.new)
+    }
+    /// Write the old bytes of this patch to the given *buffer*, undoing it.
+    pub fn revert<B: BufferMut + ?Sized>(&self, buffer: &mut B) -> Result<(), Error> {
+        buffer.write(self.offset, &self.old)
+    }
+}
+
+/// The bytes at an offset before a change. Undoing a change swaps them back into the buffer,
+/// which leaves the change holding the bytes needed to redo it.
+#[derive(Clone, Debug)]
+struct Change {
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+/// A [`BufferMut`](BufferMut) wrapper which records the old bytes before every change, so that changes
+/// can be rolled back, undone and redone.
+///
+/// Changes can be grouped into transactions with [`TransactionalBuffer::begin`](TransactionalBuffer::begin),
+/// which nest. [`TransactionalBuffer::rollback`](TransactionalBuffer::rollback) reverts the changes of the
+/// innermost transaction, and [`TransactionalBuffer::commit`](TransactionalBuffer::commit) keeps them. Once the
+/// outermost transaction is committed, its changes become a single step of the undo history. Changes made outside
+/// of a transaction are a step of their own.
+///
+/// Writes through [`BufferMut::write`](BufferMut::write) and the functions built on it, such as
+/// [`BufferMut::write_ref`](BufferMut::write_ref), only record the bytes they touch, as do
+/// [`BufferMut::get_mut_ref`](BufferMut::get_mut_ref) and [`BufferMut::get_mut_slice_ref`](BufferMut::get_mut_slice_ref).
+/// Anything which needs the whole mutable slice, such as [`BufferMut::fill`](BufferMut::fill), records the whole buffer.
+///
+/// # Example
+/// ```rust
+/// use pkbuffer::{Buffer, BufferMut, TransactionalBuffer, VecBuffer};
+///
+/// let mut buffer = TransactionalBuffer::new(VecBuffer::from_data([0u8; 4]));
+/// buffer.write_val::<u16>(0, 0xFACE).unwrap();
+///
+/// buffer.begin();
+/// buffer.write(2, [0xAA, 0xBB]).unwrap();
+/// buffer.rollback();
+/// assert_eq!(buffer.as_slice(), [0xCE, 0xFA, 0x00, 0x00]);
+///
+/// assert!(buffer.undo());
+/// assert_eq!(buffer.as_slice(), [0x00; 4]);
+/// assert!(buffer.redo());
+/// assert_eq!(buffer.to_patch()[0].new, [0xCE, 0xFA]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TransactionalBuffer<B: BufferMut> {
+    buffer: B,
+    undo: VecDeque<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    pending: Vec<Change>,
+    transactions: Vec<usize>,
+    limit: usize,
+}
+impl<B: BufferMut> TransactionalBuffer<B> {
+    /// The number of steps of undo history kept by [`TransactionalBuffer::new`](TransactionalBuffer::new).
+    pub const DEFAULT_LIMIT: usize = 100;
+
+    /// Wrap the given *buffer*, keeping [`TransactionalBuffer::DEFAULT_LIMIT`](TransactionalBuffer::DEFAULT_LIMIT)
+    /// steps of undo history.
+    pub fn new(buffer: B) -> Self {
+        Self::with_limit(buffer, Self::DEFAULT_LIMIT)
+    }
+    /// Wrap the given *buffer*, keeping at most *limit* steps of undo history. The oldest steps are
+    /// forgotten once the limit is reached.
+    pub fn with_limit(buffer: B, limit: usize) -> Self {
+        Self { buffer, undo: VecDeque::new(), redo: Vec::new(), pending: Vec::new(), transactions: Vec::new(), limit }
+    }
+    /// Get a reference to the wrapped buffer.
+    pub fn inner(&self) -> &B {
+        &self.buffer
+    }
+    /// Unwrap the buffer, keeping all changes and discarding the history.
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+    /// Get the number of open transactions.
+    pub fn depth(&self) -> usize {
+        self.transactions.len()
+    }
+    /// Start a new transaction, nested within any open transaction.
+    pub fn begin(&mut self) {
+        self.transactions.push(self.pending.len());
+    }
+    /// Commit the innermost transaction, returning `false` if no transaction is open.
+    ///
+    /// The changes of a nested transaction become part of its parent. The changes of the outermost
+    /// transaction become one step of the undo history.
+    pub fn commit(&mut self) -> bool {
+        if self.transactions.pop().is_none() { return false; }
+
+        if self.transactions.is_empty() && !self.pending.is_empty() {
+            let changes = std::mem::take(&mut self.pending);
+            self.push_undo(changes);
+        }
+
+        true
+    }
+    /// Revert the changes of the innermost transaction and close it, returning `false` if no transaction is open.
+    pub fn rollback(&mut self) -> bool {
+        let start = match self.transactions.pop() {
+            Some(start) => start,
+            None => return false,
+        };
+
+        let mut changes = self.pending.split_off(start);
+        self.swap_changes(changes.iter_mut().rev());
+        true
+    }
+    /// Check whether or not there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        self.transactions.is_empty() && !self.undo.is_empty()
+    }
+    /// Check whether or not there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        self.transactions.is_empty() && !self.redo.is_empty()
+    }
+    /// Undo the most recent step, returning `false` if there is nothing to undo or a transaction is open.
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() { return false; }
+
+        let mut changes = self.undo.pop_back().unwrap();
+        self.swap_changes(changes.iter_mut().rev());
+        self.redo.push(changes);
+        true
+    }
+    /// Redo the most recently undone step, returning `false` if there is nothing to redo or a transaction is open.
+    ///
+    /// Making a new change forgets every undone step.
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() { return false; }
+
+        let mut changes = self.redo.pop().unwrap();
+        self.swap_changes(changes.iter_mut());
+        self.undo.push_back(changes);
+        true
+    }
+    /// Forget the undo and redo history. Changes of open transactions can still be rolled back.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+    /// Export the net effect of the undo history and any open transactions as a list of changed byte runs.
+    ///
+    /// Bytes which were changed and then changed back are left out, as are steps which fell off the end
+    /// of the history.
+    pub fn to_patch(&self) -> Vec<BytePatch> {
+        let mut original = BTreeMap::<usize, u8>::new();
+
+        // walk from the newest change to the oldest, so the oldest bytes win
+        for change in self.undo.iter().flatten().chain(self.pending.iter()).rev() {
+            for (index, byte) in change.bytes.iter().enumerate() {
+                original.insert(change.offset+index, *byte);
+            }
+        }
+
+        let current = self.buffer.as_slice();
+        let mut patches = Vec::<BytePatch>::new();
+
+        for (offset, old) in original {
+            let new = current[offset];
+            if old == new { continue; }
+
+            match patches.last_mut() {
+                Some(patch) if patch.offset + patch.old.len() == offset => {
+                    patch.old.push(old);
+                    patch.new.push(new);
+                },
+                _ => patches.push(BytePatch { offset, old: vec![old], new: vec![new] }),
+            }
+        }
+
+        patches
+    }
+    /// Copy the bytes from *offset* to *offset* + *size* before they change. Ranges which are out of
+    /// bounds are ignored, since the operation about to happen will fail.
+    fn snapshot(&self, offset: usize, size: usize) -> Option<Change> {
+        let end = match offset.checked_add(size) {
+            Some(end) if size != 0 && end <= self.buffer.len() => end,
+            _ => return None,
+        };
+
+        Some(Change { offset, bytes: self.buffer.as_slice()[offset..end].to_vec() })
+    }
+    /// Record the bytes from *offset* to *offset* + *size* before an operation which can't fail changes them.
+    fn record(&mut self, offset: usize, size: usize) {
+        if let Some(change) = self.snapshot(offset, size) { self.push_change(change); }
+    }
+    /// Add a change to the history once the operation which made it has succeeded.
+    fn push_change(&mut self, change: Change) {
+        self.redo.clear();
+
+        if self.transactions.is_empty() { self.push_undo(vec![change]); }
+        else { self.pending.push(change); }
+    }
+    fn push_undo(&mut self, changes: Vec<Change>) {
+        self.undo.push_back(changes);
+
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+    fn swap_changes<'a, I: Iterator<Item = &'a mut Change>>(&mut self, changes: I) {
+        let data = self.buffer.as_mut_slice();
+
+        for change in changes {
+            data[change.offset..change.offset+change.bytes.len()].swap_with_slice(&mut change.bytes);
+        }
+    }
+}
+impl<B: BufferMut> Buffer for TransactionalBuffer<B> {
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+    fn as_ptr(&self) -> *const u8 {
+        self.buffer.as_ptr()
+    }
+    fn as_slice(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+}
+impl<B: BufferMut> BufferMut for TransactionalBuffer<B> {
+    /// Get a mutable pointer to the buffer. Since any byte may change, this records the whole buffer.
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.record(0, self.buffer.len());
+        self.buffer.as_mut_ptr()
+    }
+    /// Get the buffer as a mutable slice. Since any byte may change, this records the whole buffer.
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.record(0, self.buffer.len());
+        self.buffer.as_mut_slice()
+    }
+    fn swap(&mut self, a: usize, b: usize) {
+        // keep both bytes in one step of the history
+        self.begin();
+        self.record(a, 1);
+        self.record(b, 1);
+        self.buffer.swap(a, b);
+        self.commit();
+    }
+    fn get_mut_slice_ref<T: Castable>(&mut self, offset: usize, size: usize) -> Result<&mut [T], Error> {
+        // check the reference first, so a failed call doesn't touch the history
+        self.buffer.get_mut_slice_ref::<T>(offset, size)?;
+
+        self.record(offset, std::mem::size_of::<T>().saturating_mul(size));
+        self.buffer.get_mut_slice_ref::<T>(offset, size)
+    }
+    fn write<D: AsRef<[u8]>>(&mut self, offset: usize, data: D) -> Result<(), Error> {
+        let data = data.as_ref();
+        let change = self.snapshot(offset, data.len());
+        self.buffer.write(offset, data)?;
+
+        if let Some(change) = change { self.push_change(change); }
+        Ok(())
+    }
+}
@@ -1,18 +1,67 @@
-use crate::{Buffer, Castable, Error, PtrBuffer, ref_to_bytes, slice_ref_to_bytes};
+use crate::{Buffer, BufferMut, Castable, Error, PtrBuffer, PtrBufferMut, ref_to_bytes, slice_ref_to_bytes};
+use crate::buffer::resolve_range;
+use std::collections::HashMap;
+
+/// Describes how offsets moved after an edit such as [`VecBuffer::splice`](VecBuffer::splice).
+///
+/// The bytes from *start* to *start* + *removed* were replaced with *inserted* new bytes. Offsets
+/// before *start* are unaffected, and offsets after the removed bytes moved by [`OffsetShift::delta`](OffsetShift::delta).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct OffsetShift {
+    /// The offset at which the edit happened.
+    pub start: usize,
+    /// The number of bytes removed.
+    pub removed: usize,
+    /// The number of bytes inserted.
+    pub inserted: usize,
+}
+impl OffsetShift {
+    /// Get the distance that offsets after the edit moved by.
+    pub fn delta(&self) -> isize {
+        self.inserted as isize - self.removed as isize
+    }
+    /// Map an *offset* from before the edit to where the same data is after the edit.
+    ///
+    /// Returns `None` if the offset pointed into the removed bytes. An offset at *start* of a pure
+    /// insertion moves along with the data it pointed at.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::OffsetShift;
+    ///
+    /// let shift = OffsetShift { start: 4, removed: 2, inserted: 8 };
+    /// assert_eq!(shift.apply(3), Some(3));
+    /// assert_eq!(shift.apply(5), None);
+    /// assert_eq!(shift.apply(6), Some(12));
+    /// ```
+    pub fn apply(&self, offset: usize) -> Option<usize> {
+        if offset < self.start { Some(offset) }
+        else if offset < self.start + self.removed { None }
+        else { Some(offset - self.removed + self.inserted) }
+    }
+}
+
+/// A handle to an offset within a [`VecBuffer`](VecBuffer) which follows its data through edits.
+///
+/// See [`VecBuffer::anchor`](VecBuffer::anchor).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Anchor(usize);
 
 /// An owned-data [`Buffer`](Buffer) object.
 #[derive(Clone, Eq, Debug)]
 pub struct VecBuffer {
     data: Vec<u8>,
+    anchors: HashMap<usize, usize>,
+    next_anchor: usize,
 }
 impl VecBuffer {
     /// Create a new ```VecBuffer``` object, similar to [`Vec::new`](Vec::new).
     pub fn new() -> Self {
-        Self { data: Vec::<u8>::new() }
+        Self { data: Vec::<u8>::new(), anchors: HashMap::new(), next_anchor: 0 }
     }
     /// Create a new `VecBuffer` object with initialization data.
     pub fn from_data<B: AsRef<[u8]>>(data: B) -> Self {
-        Self { data: data.as_ref().to_vec() }
+        Self { data: data.as_ref().to_vec(), anchors: HashMap::new(), next_anchor: 0 }
     }
     /// Create a new ```VecBuffer``` from the given file data.
     pub fn from_file<P: AsRef<std::path::Path>>(filename: P) -> Result<Self, Error> {
@@ -21,17 +70,21 @@ impl VecBuffer {
             Err(e) => return Err(Error::from(e)),
         };
 
-        Ok(Self { data })
+        Ok(Self { data, anchors: HashMap::new(), next_anchor: 0 })
     }
     /// Create a new ```VecBuffer``` with a given starting size. This will zero out the
     /// buffer on initialization.
     pub fn with_initial_size(size: usize) -> Self {
-        Self::from_data(&vec![0u8; size])
+        Self::from_data(vec![0u8; size])
     }
     /// Create a [`PtrBuffer`](PtrBuffer) object from this `VecBuffer` object.
     pub fn as_ptr_buffer(&self) -> PtrBuffer {
         PtrBuffer::new(self.data.as_ptr(), self.data.len())
     }
+    /// Create a [`PtrBufferMut`](PtrBufferMut) object from this `VecBuffer` object.
+    pub fn as_ptr_buffer_mut(&mut self) -> PtrBufferMut {
+        PtrBufferMut::new(self.data.as_mut_ptr(), self.data.len())
+    }
     /// Appends the given data to the end of the buffer. This resizes and expands the underlying vector.
     pub fn append<B: AsRef<[u8]>>(&mut self, data: B) {
         self.data.append(&mut data.as_ref().to_vec());
@@ -47,12 +100,78 @@ impl VecBuffer {
         self.append(bytes); Ok(())
     }
     /// Insert a given *element* at the given *offset*, expanding the vector by one. See [`Vec::insert`](Vec::insert).
+    ///
+    /// Like [`VecBuffer::insert_bytes`](VecBuffer::insert_bytes), this updates any [`Anchor`](Anchor) objects.
     pub fn insert(&mut self, offset: usize, element: u8) {
         self.data.insert(offset, element);
+        self.shift_anchors(OffsetShift { start: offset, removed: 0, inserted: 1 });
     }
     /// Remove a given element at the given *offset*, shrinking the vector by one. See [`Vec::remove`](Vec::remove).
+    ///
+    /// Like [`VecBuffer::splice`](VecBuffer::splice), this updates any [`Anchor`](Anchor) objects.
     pub fn remove(&mut self, offset: usize) {
         self.data.remove(offset);
+        self.shift_anchors(OffsetShift { start: offset, removed: 1, inserted: 0 });
+    }
+    /// Replace the bytes in the given *range* with *data*, which may be of a different size.
+    ///
+    /// Returns an [`OffsetShift`](OffsetShift) describing how offsets after the range moved, and updates any
+    /// [`Anchor`](Anchor) objects registered with [`VecBuffer::anchor`](VecBuffer::anchor). Anchors within
+    /// the replaced range are invalidated. Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the
+    /// range is out of bounds of the buffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use pkbuffer::{Buffer, VecBuffer};
+    ///
+    /// let mut buffer = VecBuffer::from_data(b"header|table|strings");
+    /// let strings = buffer.anchor(13);
+    ///
+    /// let shift = buffer.splice(7..12, b"bigger table").unwrap();
+    /// assert_eq!(shift.delta(), 7);
+    /// assert_eq!(buffer, *b"header|bigger table|strings");
+    /// assert_eq!(buffer.anchor_offset(strings), Some(20));
+    /// ```
+    pub fn splice<R: std::ops::RangeBounds<usize>, B: AsRef<[u8]>>(&mut self, range: R, data: B) -> Result<OffsetShift, Error> {
+        let std::ops::Range { start, end } = resolve_range(self.len(), range)?;
+
+        let data = data.as_ref();
+        self.data.splice(start..end, data.iter().copied());
+
+        let shift = OffsetShift { start, removed: end - start, inserted: data.len() };
+        self.shift_anchors(shift);
+        Ok(shift)
+    }
+    /// Insert *data* at the given *offset*, moving everything after it. See [`VecBuffer::splice`](VecBuffer::splice).
+    pub fn insert_bytes<B: AsRef<[u8]>>(&mut self, offset: usize, data: B) -> Result<OffsetShift, Error> {
+        self.splice(offset..offset, data)
+    }
+    /// Register an [`Anchor`](Anchor) at the given *offset*.
+    ///
+    /// Anchors are moved by [`VecBuffer::splice`](VecBuffer::splice), [`VecBuffer::insert_bytes`](VecBuffer::insert_bytes),
+    /// [`VecBuffer::insert`](VecBuffer::insert) and [`VecBuffer::remove`](VecBuffer::remove), so they keep pointing at
+    /// the same data. Other methods which resize the buffer leave them alone. Handles are never reused, so an
+    /// anchor which was removed or invalidated never starts tracking another anchor's offset.
+    pub fn anchor(&mut self, offset: usize) -> Anchor {
+        let anchor = Anchor(self.next_anchor);
+        self.next_anchor += 1;
+        self.anchors.insert(anchor.0, offset);
+        anchor
+    }
+    /// Get the current offset of the given *anchor*, or `None` if the data it pointed at was removed.
+    pub fn anchor_offset(&self, anchor: Anchor) -> Option<usize> {
+        self.anchors.get(&anchor.0).copied()
+    }
+    /// Stop tracking the given *anchor*.
+    pub fn remove_anchor(&mut self, anchor: Anchor) {
+        self.anchors.remove(&anchor.0);
+    }
+    fn shift_anchors(&mut self, shift: OffsetShift) {
+        // anchors into removed data are dropped, so edits only ever walk live anchors
+        self.anchors.retain(|_, offset| match shift.apply(*offset) {
+            Some(moved) => { *offset = moved; true },
+            None => false,
+        });
     }
     /// Retains only the elements specified by the predicate. See [`Vec::retain`](Vec::retain).
     pub fn retain<F>(&mut self, f: F)
@@ -98,6 +217,11 @@ impl VecBuffer {
         self.data.dedup();
     }
 }
+impl Default for VecBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Buffer for VecBuffer {
     /// Get the length of this `VecBuffer` object.
     fn len(&self) -> usize {
@@ -107,14 +231,16 @@ impl Buffer for VecBuffer {
     fn as_ptr(&self) -> *const u8 {
         self.data.as_ptr()
     }
-    /// Get the `VecBuffer` object as a mutable pointer.
-    fn as_mut_ptr(&mut self) -> *mut u8 {
-        self.data.as_mut_ptr()
-    }
     /// Get the `VecBuffer` object as a slice.
     fn as_slice(&self) -> &[u8] {
         self.data.as_slice()
     }
+}
+impl BufferMut for VecBuffer {
+    /// Get the `VecBuffer` object as a mutable pointer.
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.data.as_mut_ptr()
+    }
     /// Get the `VecBuffer` object as a mutable slice.
     fn as_mut_slice(&mut self) -> &mut [u8]
     {
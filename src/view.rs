@@ -0,0 +1,204 @@
+use crate::{Castable, Error, ref_to_bytes};
+use std::marker::PhantomData;
+
+/// Calculate the number of bytes spanned by *count* elements of type *T* spaced *stride* bytes apart.
+fn view_span<T: Castable>(count: usize, stride: usize) -> Result<usize, Error> {
+    let size = std::mem::size_of::<T>();
+
+    if size == 0 { return Err(Error::ZeroSizedType); }
+    if stride < size { return Err(Error::SizeMismatch(size,stride)); }
+    if count == 0 { return Ok(0); }
+
+    match (count-1).checked_mul(stride).and_then(|span| span.checked_add(size)) {
+        Some(span) => Ok(span),
+        None => Err(Error::OutOfBounds(usize::MAX,count)),
+    }
+}
+
+/// A read-only view of an array of [`Castable`](Castable) elements within a byte slice.
+///
+/// Elements are copied out of the underlying data, so the view works at any alignment.
+/// Elements can be spaced further apart than their size with a *stride*, which is useful
+/// for walking tables whose entries are larger than the part you care about.
+///
+/// See [`Buffer::view`](crate::Buffer::view) and [`Buffer::view_strided`](crate::Buffer::view_strided).
+#[derive(Debug)]
+pub struct TypedView<'a, T: Castable> {
+    data: &'a [u8],
+    count: usize,
+    stride: usize,
+    _marker: PhantomData<T>,
+}
+impl<'a, T: Castable> TypedView<'a, T> {
+    /// Create a new view of *count* elements of type *T* spaced *stride* bytes apart at the start of *data*.
+    ///
+    /// Returns an [`Error::SizeMismatch`](Error::SizeMismatch) error if the stride is smaller than *T*,
+    /// an [`Error::ZeroSizedType`](Error::ZeroSizedType) error if *T* is zero-sized and an
+    /// [`Error::OutOfBounds`](Error::OutOfBounds) error if the elements don't fit within *data*.
+    pub fn new(data: &'a [u8], count: usize, stride: usize) -> Result<Self, Error> {
+        let span = view_span::<T>(count, stride)?;
+
+        if span > data.len() { return Err(Error::OutOfBounds(data.len(),span)); }
+
+        Ok(Self { data: &data[..span], count, stride, _marker: PhantomData })
+    }
+    /// Get the number of elements in this view.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+    /// Check whether or not this view is empty.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// Get the distance in bytes between the start of each element.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+    /// Get a copy of the element at the given *index*, or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.count { return None; }
+
+        Some(unsafe { std::ptr::read_unaligned(self.data.as_ptr().add(index * self.stride) as *const T) })
+    }
+    /// Get a copy of the first element of this view.
+    pub fn first(&self) -> Option<T> {
+        self.get(0)
+    }
+    /// Get a copy of the last element of this view.
+    pub fn last(&self) -> Option<T> {
+        if self.count == 0 { None }
+        else { self.get(self.count-1) }
+    }
+    /// Return an iterator over copies of the elements of this view.
+    pub fn iter(&self) -> TypedViewIter<'a, T> {
+        TypedViewIter { view: *self, index: 0, end: self.count }
+    }
+    /// Copy the elements of this view into a [`Vec`](Vec).
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+impl<'a, T: Castable> Clone for TypedView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, T: Castable> Copy for TypedView<'a, T> {}
+impl<'a, T: Castable> IntoIterator for TypedView<'a, T> {
+    type Item = T;
+    type IntoIter = TypedViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, T: Castable> IntoIterator for &TypedView<'a, T> {
+    type Item = T;
+    type IntoIter = TypedViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable view of an array of [`Castable`](Castable) elements within a byte slice.
+///
+/// See [`TypedView`](TypedView), [`BufferMut::view_mut`](crate::BufferMut::view_mut) and
+/// [`BufferMut::view_mut_strided`](crate::BufferMut::view_mut_strided).
+#[derive(Debug)]
+pub struct TypedViewMut<'a, T: Castable> {
+    data: &'a mut [u8],
+    count: usize,
+    stride: usize,
+    _marker: PhantomData<T>,
+}
+impl<'a, T: Castable> TypedViewMut<'a, T> {
+    /// Create a new mutable view of *count* elements of type *T* spaced *stride* bytes apart at the start of *data*.
+    ///
+    /// See [`TypedView::new`](TypedView::new) for errors.
+    pub fn new(data: &'a mut [u8], count: usize, stride: usize) -> Result<Self, Error> {
+        let span = view_span::<T>(count, stride)?;
+
+        if span > data.len() { return Err(Error::OutOfBounds(data.len(),span)); }
+
+        Ok(Self { data: &mut data[..span], count, stride, _marker: PhantomData })
+    }
+    /// Get a read-only [`TypedView`](TypedView) of this view.
+    pub fn as_view(&self) -> TypedView<'_, T> {
+        TypedView { data: self.data, count: self.count, stride: self.stride, _marker: PhantomData }
+    }
+    /// Get the number of elements in this view.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+    /// Check whether or not this view is empty.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// Get the distance in bytes between the start of each element.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+    /// Get a copy of the element at the given *index*, or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.as_view().get(index)
+    }
+    /// Overwrite the element at the given *index* with *value*.
+    ///
+    /// Returns an [`Error::OutOfBounds`](Error::OutOfBounds) error if the index is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        if index >= self.count { return Err(Error::OutOfBounds(self.count,index)); }
+
+        let bytes = ref_to_bytes::<T>(&value)?;
+        let start = index * self.stride;
+        self.data[start..start+bytes.len()].copy_from_slice(bytes);
+
+        Ok(())
+    }
+    /// Return an iterator over copies of the elements of this view.
+    pub fn iter(&self) -> TypedViewIter<'_, T> {
+        self.as_view().iter()
+    }
+    /// Copy the elements of this view into a [`Vec`](Vec).
+    pub fn to_vec(&self) -> Vec<T> {
+        self.as_view().to_vec()
+    }
+}
+
+/// An iterator over copies of the elements of a [`TypedView`](TypedView).
+#[derive(Debug)]
+pub struct TypedViewIter<'a, T: Castable> {
+    view: TypedView<'a, T>,
+    index: usize,
+    end: usize,
+}
+impl<'a, T: Castable> Clone for TypedViewIter<'a, T> {
+    fn clone(&self) -> Self {
+        Self { view: self.view, index: self.index, end: self.end }
+    }
+}
+impl<'a, T: Castable> Iterator for TypedViewIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end { return None; }
+
+        let result = self.view.get(self.index);
+        self.index += 1;
+
+        result
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, T: Castable> DoubleEndedIterator for TypedViewIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end { return None; }
+
+        self.end -= 1;
+        self.view.get(self.end)
+    }
+}
+impl<'a, T: Castable> ExactSizeIterator for TypedViewIter<'a, T> {}